@@ -0,0 +1,414 @@
+//! Control-flow analysis: basic blocks, the control-flow graph and its natural loops, with the
+//! registers every loop reads and writes and the `tgl` instructions that may rewrite it.
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use super::disasm::jump_target;
+use super::{Instruction, Operand};
+
+/// A maximal run of instructions that is only entered at its first instruction and only left
+/// after its last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// Positions of the instructions in the block.
+    pub range: Range<usize>,
+    /// Indices of the blocks that may execute next.
+    pub successors: Vec<usize>,
+    /// Whether control may leave the program after this block, halting it.
+    pub exits: bool,
+    /// Whether the block ends in a jump whose offset is only known at runtime. Such a jump has
+    /// no successors in the graph beyond falling through.
+    pub indirect: bool,
+}
+
+/// The position a `tgl` instruction rewrites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TglTarget {
+    /// The offset is a literal, so the target is known. It may lie outside the program, in
+    /// which case the `tgl` does nothing.
+    Exact(i64),
+    /// The offset is read from a register, so any instruction may be rewritten.
+    Unknown,
+}
+
+/// Returns what the instruction at `pos` toggles, if it is a `tgl`.
+pub fn tgl_target(pos: usize, instruction: &Instruction) -> Option<TglTarget> {
+    match instruction {
+        Instruction::Tgl(Operand::Imm(offset)) => Some(TglTarget::Exact(pos as i64 + offset)),
+        Instruction::Tgl(Operand::Reg(_)) => Some(TglTarget::Unknown),
+        _ => None,
+    }
+}
+
+/// A natural loop: a header block that dominates the blocks jumping back to it, together with
+/// every block that can reach those jumps without passing through the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    /// Index of the header block.
+    pub header: usize,
+    /// Indices of the blocks in the loop, including the header.
+    pub blocks: BTreeSet<usize>,
+    /// Positions of the instructions in the loop.
+    pub instructions: BTreeSet<usize>,
+    /// Registers read by any instruction in the loop.
+    pub reads: BTreeSet<usize>,
+    /// Registers written by any instruction in the loop.
+    pub writes: BTreeSet<usize>,
+    /// Positions of the `tgl` instructions, anywhere in the program, that may rewrite an
+    /// instruction of the loop.
+    pub rewritten_by: Vec<usize>,
+}
+
+/// The control-flow graph of a program, as it is before any `tgl` fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    pub blocks: Vec<Block>,
+    /// The block each instruction belongs to.
+    block_of: Vec<usize>,
+}
+
+impl Cfg {
+    /// Splits `instructions` into basic blocks and connects them.
+    ///
+    /// A `jnz` with a register offset is resolved when the register is loaded with a literal by
+    /// the instruction right before it, and the jump is not a jump target itself; this is how
+    /// assembunny programs encode long jumps. Other register offsets make the block
+    /// [`indirect`](Block::indirect).
+    pub fn new(instructions: &[Instruction]) -> Self {
+        let len = instructions.len() as i64;
+        let literal_targets: BTreeSet<usize> = instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, instruction)| jump_target(pos, instruction))
+            .filter(|target| (0..len).contains(target))
+            .map(|target| target as usize)
+            .collect();
+
+        let next: Vec<(Vec<i64>, bool)> = (0..instructions.len())
+            .map(|pos| next_positions(instructions, pos, &literal_targets))
+            .collect();
+
+        let mut leaders = BTreeSet::from([0]);
+        for (pos, (targets, _)) in next.iter().enumerate() {
+            if let Instruction::Jnz(..) = instructions[pos] {
+                leaders.extend(
+                    targets
+                        .iter()
+                        .chain(&[pos as i64 + 1])
+                        .filter(|target| (0..len).contains(*target))
+                        .map(|&target| target as usize),
+                );
+            }
+        }
+
+        let starts: Vec<usize> = leaders
+            .into_iter()
+            .filter(|&start| start < instructions.len())
+            .collect();
+        let mut block_of = vec![0; instructions.len()];
+        for (block, start) in starts.iter().enumerate() {
+            let end = starts.get(block + 1).copied().unwrap_or(instructions.len());
+            block_of[*start..end].fill(block);
+        }
+
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(block, &start)| {
+                let end = starts.get(block + 1).copied().unwrap_or(instructions.len());
+                let (targets, indirect) = &next[end - 1];
+                let mut successors: Vec<usize> = targets
+                    .iter()
+                    .filter(|target| (0..len).contains(*target))
+                    .map(|&target| block_of[target as usize])
+                    .collect();
+                successors.sort_unstable();
+                successors.dedup();
+                Block {
+                    range: start..end,
+                    successors,
+                    exits: targets.iter().any(|target| !(0..len).contains(target)),
+                    indirect: *indirect,
+                }
+            })
+            .collect();
+
+        Self { blocks, block_of }
+    }
+
+    /// The block containing the instruction at `pos`.
+    pub fn block_of(&self, pos: usize) -> usize {
+        self.block_of[pos]
+    }
+
+    /// The blocks that may execute right before each block.
+    pub fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![vec![]; self.blocks.len()];
+        for (block, b) in self.blocks.iter().enumerate() {
+            for &successor in &b.successors {
+                predecessors[successor].push(block);
+            }
+        }
+        predecessors
+    }
+
+    /// The immediate dominator of every block: the last block every path from the entry has to
+    /// pass through before reaching it. The entry block is its own immediate dominator, and
+    /// unreachable blocks have none.
+    pub fn immediate_dominators(&self) -> Vec<Option<usize>> {
+        let mut idom = vec![None; self.blocks.len()];
+        if self.blocks.is_empty() {
+            return idom;
+        }
+
+        // reverse postorder, computed with an explicit stack of (block, next successor).
+        let mut postorder = vec![];
+        let mut visited = vec![false; self.blocks.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, i)) = stack.pop() {
+            match self.blocks[block].successors.get(i) {
+                Some(&successor) => {
+                    stack.push((block, i + 1));
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                }
+                None => postorder.push(block),
+            }
+        }
+        let mut order = vec![usize::MAX; self.blocks.len()];
+        for (i, &block) in postorder.iter().rev().enumerate() {
+            order[block] = i;
+        }
+
+        // Cooper, Harvey and Kennedy, "A Simple, Fast Dominance Algorithm".
+        let predecessors = self.predecessors();
+        let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+            while a != b {
+                while order[a] > order[b] {
+                    a = idom[a].unwrap();
+                }
+                while order[b] > order[a] {
+                    b = idom[b].unwrap();
+                }
+            }
+            a
+        };
+        idom[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in postorder.iter().rev().skip(1) {
+                let new_idom = predecessors[block]
+                    .iter()
+                    .filter(|&&p| idom[p].is_some())
+                    .fold(None, |new_idom, &p| match new_idom {
+                        None => Some(p),
+                        Some(d) => Some(intersect(&idom, d, p)),
+                    });
+                if idom[block] != new_idom {
+                    idom[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        idom
+    }
+
+    /// Finds the natural loops of the program, ordered by header. Loops sharing a header are
+    /// merged into one.
+    ///
+    /// Blocks that are only reachable through an [`indirect`](Block::indirect) jump are not
+    /// analysed, so loops among them are not found.
+    pub fn loops(&self, instructions: &[Instruction]) -> Vec<Loop> {
+        let idom = self.immediate_dominators();
+        let dominates = |a: usize, mut b: usize| loop {
+            if a == b {
+                return true;
+            }
+            match idom[b] {
+                Some(d) if d != b => b = d,
+                _ => return false,
+            }
+        };
+
+        let predecessors = self.predecessors();
+        let mut bodies: Vec<(usize, BTreeSet<usize>)> = vec![];
+        for (tail, block) in self.blocks.iter().enumerate() {
+            for &header in &block.successors {
+                if idom[tail].is_none() || !dominates(header, tail) {
+                    continue;
+                }
+                let mut body = BTreeSet::from([header]);
+                let mut stack = vec![tail];
+                while let Some(b) = stack.pop() {
+                    if body.insert(b) {
+                        stack.extend(&predecessors[b]);
+                    }
+                }
+                match bodies.iter_mut().find(|(h, _)| *h == header) {
+                    Some((_, blocks)) => blocks.extend(body),
+                    None => bodies.push((header, body)),
+                }
+            }
+        }
+        bodies.sort_unstable_by_key(|(header, _)| *header);
+
+        let toggles: Vec<(usize, TglTarget)> = instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, instruction)| Some((pos, tgl_target(pos, instruction)?)))
+            .collect();
+
+        bodies
+            .into_iter()
+            .map(|(header, blocks)| {
+                let positions: BTreeSet<usize> = blocks
+                    .iter()
+                    .flat_map(|&b| self.blocks[b].range.clone())
+                    .collect();
+                let mut reads = BTreeSet::new();
+                let mut writes = BTreeSet::new();
+                for &pos in &positions {
+                    let (read, written) = accesses(&instructions[pos]);
+                    reads.extend(read);
+                    writes.extend(written);
+                }
+                let rewritten_by = toggles
+                    .iter()
+                    .filter(|(_, target)| match *target {
+                        TglTarget::Exact(target) => {
+                            usize::try_from(target).is_ok_and(|target| positions.contains(&target))
+                        }
+                        TglTarget::Unknown => true,
+                    })
+                    .map(|(pos, _)| *pos)
+                    .collect();
+                Loop {
+                    header,
+                    blocks,
+                    instructions: positions,
+                    reads,
+                    writes,
+                    rewritten_by,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The positions control may continue at after the instruction at `pos`, which may lie outside
+/// the program, and whether it may also jump to a position only known at runtime.
+fn next_positions(
+    instructions: &[Instruction],
+    pos: usize,
+    literal_targets: &BTreeSet<usize>,
+) -> (Vec<i64>, bool) {
+    let next = pos as i64 + 1;
+    let Instruction::Jnz(condition, offset) = instructions[pos] else {
+        return (vec![next], false);
+    };
+
+    let offset = match offset {
+        Operand::Imm(offset) => Some(offset),
+        Operand::Reg(r) => match pos.checked_sub(1).map(|prev| instructions[prev]) {
+            Some(Instruction::Cpy(Operand::Imm(value), Operand::Reg(dst)))
+                if dst == r && !literal_targets.contains(&pos) =>
+            {
+                Some(value)
+            }
+            _ => None,
+        },
+    };
+    let jump = offset.map(|offset| pos as i64 + offset);
+    match condition {
+        Operand::Imm(0) => (vec![next], false),
+        Operand::Imm(_) => (jump.into_iter().collect(), jump.is_none()),
+        Operand::Reg(_) => (
+            [Some(next), jump].into_iter().flatten().collect(),
+            jump.is_none(),
+        ),
+    }
+}
+
+/// The registers an instruction reads and the register it writes. Instructions that `tgl` turned
+/// into writes to a literal are skipped by the machines, so they access nothing.
+fn accesses(instruction: &Instruction) -> (Vec<usize>, Option<usize>) {
+    let reg = |operand: Operand| match operand {
+        Operand::Reg(r) => Some(r),
+        Operand::Imm(_) => None,
+    };
+    match *instruction {
+        Instruction::Cpy(src, Operand::Reg(dst)) => (reg(src).into_iter().collect(), Some(dst)),
+        Instruction::Inc(Operand::Reg(r)) | Instruction::Dec(Operand::Reg(r)) => (vec![r], Some(r)),
+        Instruction::Jnz(a, b) => ([reg(a), reg(b)].into_iter().flatten().collect(), None),
+        Instruction::Tgl(a) | Instruction::Out(a) => (reg(a).into_iter().collect(), None),
+        Instruction::Cpy(_, Operand::Imm(_))
+        | Instruction::Inc(Operand::Imm(_))
+        | Instruction::Dec(Operand::Imm(_)) => (vec![], None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::parse_program;
+    use crate::assembunny::tests::MULTIPLY;
+
+    #[test]
+    fn builds_blocks() {
+        let cfg = Cfg::new(&parse_program(MULTIPLY).unwrap());
+
+        let starts: Vec<usize> = cfg.blocks.iter().map(|b| b.range.start).collect();
+        assert_eq!(starts, vec![0, 4, 5, 8, 10, 12]);
+        // `jnz d -5` jumps back to the start of the outer loop.
+        assert_eq!(cfg.blocks[3].successors, vec![1, 4]);
+        assert_eq!(cfg.blocks[2].successors, vec![2, 3]);
+        // the final `jnz c -2` can leave the program.
+        assert!(cfg.blocks[5].exits);
+        assert_eq!(cfg.block_of(6), 2);
+
+        let idom = cfg.immediate_dominators();
+        assert_eq!(idom[3], Some(2));
+        assert_eq!(idom[5], Some(4));
+
+        // `jnz 1 c` can't be resolved, and nothing reaches `inc a`.
+        let cfg = Cfg::new(&parse_program("jnz 1 c\njnz 1 2\ninc a\ninc b").unwrap());
+        assert!(cfg.blocks[0].indirect);
+        assert_eq!(cfg.immediate_dominators()[2], None);
+    }
+
+    #[test]
+    fn finds_loops() {
+        let instructions = parse_program(MULTIPLY).unwrap();
+        let loops = Cfg::new(&instructions).loops(&instructions);
+
+        let headers: Vec<usize> = loops.iter().map(|l| l.header).collect();
+        assert_eq!(headers, vec![1, 2, 5]);
+
+        // the multiplication: a += b * d, using c as a counter.
+        let multiply = &loops[0];
+        assert_eq!(multiply.instructions, (4..10).collect());
+        assert_eq!(multiply.reads, BTreeSet::from([0, 1, 2, 3]));
+        assert_eq!(multiply.writes, BTreeSet::from([0, 2, 3]));
+        assert_eq!(multiply.blocks, (1..4).collect());
+
+        // `tgl b` may hit anything.
+        assert!(loops.iter().all(|l| l.rewritten_by == vec![10]));
+    }
+
+    #[test]
+    fn flags_toggled_loops() {
+        let instructions = parse_program("cpy 3 b\ntgl 2\ninc a\ninc b\njnz b -2").unwrap();
+        let loops = Cfg::new(&instructions).loops(&instructions);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].rewritten_by, vec![1]);
+
+        let instructions = parse_program("tgl 3\ninc a\njnz a -1\ninc b").unwrap();
+        let loops = Cfg::new(&instructions).loops(&instructions);
+        assert_eq!(loops.len(), 1);
+        assert!(loops[0].rewritten_by.is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! A bytecode backend: every instruction is compiled into an [`Op`] with its operands already
+//! resolved, and the loops recognised by the [`optimizer`] are fused into single ops.
+//!
+//! When `tgl` rewrites an instruction, only the ops that could include it are recompiled.
+use super::optimizer::{self, MAX_MACRO_LEN, MacroOp};
+use super::{
+    Instruction, Machine, Operand, Snapshot, Step, Word, register_count, toggle_instruction,
+};
+
+/// A pre-resolved instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op<T = i32> {
+    CpyReg {
+        src: usize,
+        dst: usize,
+    },
+    CpyImm {
+        value: T,
+        dst: usize,
+    },
+    Inc(usize),
+    Dec(usize),
+    /// `jnz` with a non-zero literal condition and a literal offset.
+    Jmp(i64),
+    /// `jnz` on a register with a literal offset.
+    JnzReg {
+        reg: usize,
+        offset: i64,
+    },
+    /// Any other `jnz`, resolved at runtime.
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+    /// Instructions without effect, i.e. `jnz 0 x` or writes to a literal produced by `tgl`.
+    Nop,
+    /// A fused loop; falls back to the single instruction if the loop cannot be fused at runtime.
+    Fused(MacroOp),
+}
+
+/// Compiles a single instruction, without looking at its neighbours.
+pub fn compile_instruction<T: Word>(instruction: Instruction) -> Op<T> {
+    use Operand::*;
+
+    match instruction {
+        Instruction::Cpy(Reg(src), Reg(dst)) => Op::CpyReg { src, dst },
+        Instruction::Cpy(Imm(value), Reg(dst)) => Op::CpyImm {
+            value: T::from_i64(value),
+            dst,
+        },
+        Instruction::Inc(Reg(r)) => Op::Inc(r),
+        Instruction::Dec(Reg(r)) => Op::Dec(r),
+        Instruction::Jnz(Imm(0), _) => Op::Nop,
+        Instruction::Jnz(Imm(_), Imm(offset)) => Op::Jmp(offset),
+        Instruction::Jnz(Reg(reg), Imm(offset)) => Op::JnzReg { reg, offset },
+        Instruction::Jnz(s, d) => Op::Jnz(s, d),
+        Instruction::Tgl(v) => Op::Tgl(v),
+        Instruction::Out(v) => Op::Out(v),
+        Instruction::Cpy(_, Imm(_)) | Instruction::Inc(Imm(_)) | Instruction::Dec(Imm(_)) => {
+            Op::Nop
+        }
+    }
+}
+
+/// Compiles the op for position `pos`, fusing a loop that starts there if there is one.
+fn compile_at<T: Word>(instructions: &[Instruction], pos: usize) -> Op<T> {
+    match optimizer::find_macro(instructions, pos) {
+        Some(op) => Op::Fused(op),
+        None => compile_instruction(instructions[pos]),
+    }
+}
+
+/// Compiles every position of the program.
+fn compile<T: Word>(instructions: &[Instruction]) -> Vec<Op<T>> {
+    (0..instructions.len())
+        .map(|pos| compile_at(instructions, pos))
+        .collect()
+}
+
+/// Executes a compiled version of the program.
+#[derive(Debug, Clone)]
+pub struct Compiled<T: Word = i32> {
+    pub regs: Vec<T>,
+    pub pos: i64,
+
+    instructions: Vec<Instruction>,
+    ops: Vec<Op<T>>,
+}
+
+impl<T: Word> Compiled<T> {
+    /// The compiled ops, one per instruction.
+    pub fn ops(&self) -> &[Op<T>] {
+        &self.ops
+    }
+
+    /// Recompiles every op that could cover the instruction at `changed`.
+    fn recompile(&mut self, changed: usize) {
+        for pos in changed.saturating_sub(MAX_MACRO_LEN - 1)..=changed {
+            self.ops[pos] = compile_at(&self.instructions, pos);
+        }
+    }
+
+    #[inline]
+    fn value(&self, operand: Operand) -> T {
+        operand.value(&self.regs)
+    }
+
+    #[inline]
+    fn execute(&mut self, op: Op<T>) -> Step<T> {
+        match op {
+            Op::CpyReg { src, dst } => {
+                self.regs[dst] = self.regs[src];
+                self.pos += 1;
+            }
+            Op::CpyImm { value, dst } => {
+                self.regs[dst] = value;
+                self.pos += 1;
+            }
+            Op::Inc(r) => {
+                self.regs[r] += T::ONE;
+                self.pos += 1;
+            }
+            Op::Dec(r) => {
+                self.regs[r] -= T::ONE;
+                self.pos += 1;
+            }
+            Op::Jmp(offset) => self.pos += offset,
+            Op::JnzReg { reg, offset } => {
+                self.pos += if self.regs[reg] != T::ZERO { offset } else { 1 };
+            }
+            Op::Jnz(s, d) => {
+                self.pos += if self.value(s) != T::ZERO {
+                    self.value(d).to_i64()
+                } else {
+                    1
+                };
+            }
+            Op::Tgl(dist) => {
+                let index_to_modify = self.pos + self.value(dist).to_i64();
+                if let Some(instruction) = self.instructions.get_mut(index_to_modify as usize) {
+                    *instruction = toggle_instruction(instruction);
+                    self.recompile(index_to_modify as usize);
+                }
+                self.pos += 1;
+            }
+            Op::Out(v) => {
+                self.pos += 1;
+                return Step::Output(self.value(v));
+            }
+            Op::Nop => self.pos += 1,
+            Op::Fused(macro_op) => {
+                if macro_op.apply(&mut self.regs) {
+                    self.pos += macro_op.span() as i64;
+                } else {
+                    let op = compile_instruction(self.instructions[self.pos as usize]);
+                    return self.execute(op);
+                }
+            }
+        }
+        Step::Continue
+    }
+}
+
+impl<T: Word> Machine for Compiled<T> {
+    type Word = T;
+
+    fn new(instructions: Vec<Instruction>) -> Self {
+        let ops = compile(&instructions);
+        Self {
+            regs: vec![T::ZERO; register_count(&instructions)],
+            pos: 0,
+
+            instructions,
+            ops,
+        }
+    }
+
+    fn restore(&mut self, snapshot: &Snapshot<T>) {
+        self.regs.clone_from(&snapshot.regs);
+        self.pos = snapshot.pos;
+        self.instructions.clone_from(&snapshot.instructions);
+        self.ops = compile(&self.instructions);
+    }
+
+    fn step(&mut self) -> Step<T> {
+        match self.ops.get(self.pos as usize) {
+            Some(&op) => self.execute(op),
+            None => Step::Halted,
+        }
+    }
+
+    fn pos(&self) -> i64 {
+        self.pos
+    }
+
+    fn regs(&self) -> &[T] {
+        &self.regs
+    }
+
+    fn regs_mut(&mut self) -> &mut [T] {
+        &mut self.regs
+    }
+
+    fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{State, parse_program};
+
+    #[test]
+    fn matches_interpreter() {
+        let instructions =
+            parse_program(&crate::template::read_file("examples", crate::day!(23))).unwrap();
+
+        let mut state: State = State::new(instructions.clone());
+        state.run();
+        let mut compiled: Compiled = Compiled::new(instructions);
+        compiled.run();
+
+        assert_eq!(compiled.regs, state.regs);
+        assert_eq!(compiled.instructions(), state.instructions);
+    }
+
+    #[test]
+    fn recompiles_toggled_instructions() {
+        let instructions = parse_program("cpy 3 b\ntgl 2\ninc a\ninc b\njnz b -2").unwrap();
+        let mut compiled: Compiled = Compiled::new(instructions);
+        assert_eq!(compiled.ops()[2], Op::Inc(0));
+
+        compiled.step();
+        compiled.step();
+        assert_eq!(
+            compiled.ops()[2],
+            Op::Fused(MacroOp::Add { dst: 0, src: 1 })
+        );
+        assert_eq!(compiled.ops()[3], Op::Dec(1));
+
+        compiled.run();
+        assert_eq!(compiled.regs, vec![3, 0, 0, 0]);
+    }
+
+    #[test]
+    fn restores_interpreter_snapshots() {
+        let instructions = parse_program("cpy 3 b\ntgl 2\ninc a\ninc b\njnz b -2").unwrap();
+        let mut state: State = State::new(instructions.clone());
+        state.step();
+        state.step();
+
+        let mut compiled: Compiled = Compiled::new(instructions);
+        compiled.restore(&state.snapshot());
+        assert_eq!(compiled.snapshot(), state.snapshot());
+        assert_eq!(compiled.ops()[3], Op::Dec(1));
+
+        compiled.run();
+        assert_eq!(compiled.regs, vec![3, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Renders programs back to assembunny source, with jump targets and toggled instructions
+//! annotated.
+use std::fmt::Write;
+
+use hashbrown::HashMap;
+
+use super::{Instruction, Operand};
+
+/// Returns the position a `jnz` with a literal offset jumps to, if any.
+pub fn jump_target(pos: usize, instruction: &Instruction) -> Option<i64> {
+    match instruction {
+        Instruction::Jnz(_, Operand::Imm(offset)) => Some(pos as i64 + *offset),
+        _ => None,
+    }
+}
+
+/// Disassembles `instructions` into source that [`parse_program`](super::parse_program) reads
+/// back into the same program.
+///
+/// Every position targeted by a `jnz` with a literal offset gets a label (`L1:`, `L2:`, ...) and
+/// the jump is annotated with the label it leads to, or `halt` if it leaves the program. When the
+/// `original` program is given, instructions that differ from it are marked as toggled.
+pub fn disassemble(instructions: &[Instruction], original: Option<&[Instruction]>) -> String {
+    let mut targets: Vec<usize> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, instruction)| jump_target(pos, instruction))
+        .filter(|target| (0..instructions.len() as i64).contains(target))
+        .map(|target| target as usize)
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+    let labels: HashMap<usize, String> = targets
+        .into_iter()
+        .enumerate()
+        .map(|(i, target)| (target, format!("L{}:", i + 1)))
+        .collect();
+    let label_width = labels.values().map(String::len).max();
+
+    let mut output = String::new();
+    for (pos, instruction) in instructions.iter().enumerate() {
+        let label = match label_width {
+            Some(width) => {
+                let label = labels.get(&pos).map(String::as_str).unwrap_or_default();
+                format!("{label:<width$} ")
+            }
+            None => String::new(),
+        };
+
+        let mut comments = vec![];
+        if let Some(target) = jump_target(pos, instruction) {
+            match usize::try_from(target).ok().and_then(|t| labels.get(&t)) {
+                Some(label) => comments.push(format!("-> {}", label.trim_end_matches(':'))),
+                None => comments.push("-> halt".to_string()),
+            }
+        }
+        if let Some(was) = original
+            .and_then(|original| original.get(pos))
+            .filter(|was| *was != instruction)
+        {
+            comments.push(format!("toggled, was `{was}`"));
+        }
+
+        let line = if comments.is_empty() {
+            format!("{label}{instruction}")
+        } else {
+            format!(
+                "{label}{:<12} ; {}",
+                instruction.to_string(),
+                comments.join(", ")
+            )
+        };
+        let _ = writeln!(output, "{}", line.trim_end());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{Machine, State, parse_program};
+
+    #[test]
+    fn round_trips() {
+        let original =
+            parse_program(&crate::template::read_file("examples", crate::day!(23))).unwrap();
+        let mut state: State = State::new(original.clone());
+        state.run();
+
+        let source = disassemble(&state.instructions, Some(&original));
+        assert_eq!(
+            source,
+            "cpy 2 a
+tgl a
+tgl a
+inc a        ; toggled, was `tgl a`
+jnz 1 a      ; toggled, was `cpy 1 a`
+dec a
+dec a
+"
+        );
+        assert_eq!(parse_program(&source).unwrap(), state.instructions);
+    }
+
+    #[test]
+    fn labels_jump_targets() {
+        let program = parse_program("cpy 41 a\ninc a\njnz a -1\njnz 1 -3\njnz 1 2").unwrap();
+
+        let source = disassemble(&program, None);
+        assert_eq!(
+            source,
+            "L1: cpy 41 a
+L2: inc a
+    jnz a -1     ; -> L2
+    jnz 1 -3     ; -> L1
+    jnz 1 2      ; -> halt
+"
+        );
+        assert_eq!(parse_program(&source).unwrap(), program);
+    }
+}
@@ -0,0 +1,234 @@
+//! Differential testing of the assembunny backends: random programs are run on the plain
+//! interpreter, the optimised interpreter and the compiler, and any disagreement is minimised
+//! into a small reproducer.
+use super::disasm::disassemble;
+use tinyjson::JsonValue;
+
+use super::{Compiled, Instruction, Machine, Operand, Program, Snapshot, State, Step};
+
+/// Number of steps after which a program is assumed not to halt.
+const MAX_STEPS: u64 = 10_000;
+
+/// Number of random programs to check.
+const PROGRAMS: u64 = 5_000;
+
+/// xorshift64*, which is plenty to generate reproducible programs without pulling in `rand`.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `min..=max`.
+    fn range(&mut self, min: i64, max: i64) -> i64 {
+        min + (self.next() % (max - min + 1) as u64) as i64
+    }
+
+    fn reg(&mut self) -> usize {
+        self.range(0, 3) as usize
+    }
+
+    /// A register or a literal in `min..=max`.
+    fn operand(&mut self, min: i64, max: i64) -> Operand {
+        if self.next().is_multiple_of(2) {
+            Operand::Reg(self.reg())
+        } else {
+            Operand::Imm(self.range(min, max))
+        }
+    }
+
+    /// `n` distinct registers.
+    fn distinct_regs(&mut self, n: usize) -> Vec<usize> {
+        let mut regs: Vec<usize> = (0..4).collect();
+        for i in 0..n {
+            let j = self.range(i as i64, 3) as usize;
+            regs.swap(i, j);
+        }
+        regs.truncate(n);
+        regs
+    }
+}
+
+/// Generates a small program. Besides random instructions, it contains the add and multiply
+/// loops the optimiser looks for, so that `tgl` gets a chance to break them up. Jumps and `tgl`
+/// offsets may point outside the program.
+fn generate(rng: &mut Rng) -> Program {
+    use Instruction::*;
+    use Operand::*;
+
+    let len = rng.range(1, 12) as usize;
+    let mut program = vec![];
+    while program.len() < len {
+        match rng.range(0, 9) {
+            0 => {
+                let [dst, src] = rng.distinct_regs(2)[..] else {
+                    unreachable!()
+                };
+                program.extend([Inc(Reg(dst)), Dec(Reg(src)), Jnz(Reg(src), Imm(-2))]);
+            }
+            1 => {
+                let [dst, tmp, count] = rng.distinct_regs(3)[..] else {
+                    unreachable!()
+                };
+                program.extend([
+                    Cpy(rng.operand(-1, 3), Reg(tmp)),
+                    Inc(Reg(dst)),
+                    Dec(Reg(tmp)),
+                    Jnz(Reg(tmp), Imm(-2)),
+                    Dec(Reg(count)),
+                    Jnz(Reg(count), Imm(-5)),
+                ]);
+            }
+            _ => program.push(match rng.range(0, 5) {
+                0 => Cpy(rng.operand(-3, 3), Reg(rng.reg())),
+                1 => Inc(Reg(rng.reg())),
+                2 => Dec(Reg(rng.reg())),
+                3 => Jnz(rng.operand(-1, 1), rng.operand(-5, 5)),
+                4 => Tgl(rng.operand(-3, 3)),
+                _ => Out(rng.operand(-3, 3)),
+            }),
+        }
+    }
+    program
+}
+
+/// How a backend finished a program.
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+    regs: Vec<i64>,
+    output: Vec<i64>,
+    instructions: Vec<Instruction>,
+}
+
+/// Runs the machine to completion, or returns `None` if it doesn't halt within [`MAX_STEPS`].
+fn execute(mut machine: impl Machine<Word = i64>, regs: &[i64]) -> Option<Outcome> {
+    machine.regs_mut().copy_from_slice(regs);
+    let mut output = vec![];
+    for _ in 0..MAX_STEPS {
+        match machine.step() {
+            Step::Continue => {}
+            Step::Output(value) => output.push(value),
+            Step::Halted => {
+                return Some(Outcome {
+                    regs: machine.regs().to_vec(),
+                    output,
+                    instructions: machine.instructions().to_vec(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Runs `program` on every backend, returning a description of the first one that disagrees with
+/// the plain interpreter.
+///
+/// Optimised backends execute fused loops as a single step, so they may halt within the budget
+/// when the interpreter does not; only programs the interpreter finishes are compared.
+fn check(program: &Program, regs: &[i64]) -> Option<String> {
+    let expected = execute(State::<i64>::new(program.clone()), regs)?;
+    let backends = [
+        (
+            "optimised interpreter",
+            execute(
+                State::<i64>::builder(program.clone()).optimize().build(),
+                regs,
+            ),
+        ),
+        (
+            "compiler",
+            execute(Compiled::<i64>::new(program.clone()), regs),
+        ),
+    ];
+    backends
+        .into_iter()
+        .find(|(_, outcome)| outcome.as_ref() != Some(&expected))
+        .map(|(backend, outcome)| {
+            format!("{backend} returned {outcome:?}, the interpreter returned {expected:?}")
+        })
+}
+
+/// Shrinks a failing program by removing instructions and moving literals towards zero for as
+/// long as it keeps failing.
+fn minimise(mut program: Program, fails: impl Fn(&Program) -> bool) -> Program {
+    let mut shrunk = true;
+    while shrunk {
+        shrunk = false;
+        for pos in (0..program.len()).rev() {
+            let mut candidate = program.clone();
+            candidate.remove(pos);
+            if fails(&candidate) {
+                program = candidate;
+                shrunk = true;
+            }
+        }
+        for pos in 0..program.len() {
+            for i in 0..program[pos].operands().len() {
+                let mut candidate = program.clone();
+                if shrink_operand(&mut candidate[pos], i) && fails(&candidate) {
+                    program = candidate;
+                    shrunk = true;
+                }
+            }
+        }
+    }
+    program
+}
+
+/// Moves the `i`th literal operand of an instruction one step towards zero.
+fn shrink_operand(instruction: &mut Instruction, i: usize) -> bool {
+    let operand = match instruction {
+        Instruction::Cpy(a, b) | Instruction::Jnz(a, b) => [a, b].into_iter().nth(i),
+        Instruction::Inc(a) | Instruction::Dec(a) | Instruction::Tgl(a) | Instruction::Out(a) => {
+            (i == 0).then_some(a)
+        }
+    };
+    match operand {
+        Some(Operand::Imm(v)) if *v != 0 => {
+            *v -= v.signum();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn backends_agree() {
+    let mut rng = Rng(0x5eed_b0a7_ca11_ab1e);
+    for _ in 0..PROGRAMS {
+        let program = generate(&mut rng);
+        let regs: Vec<i64> = (0..4).map(|_| rng.range(-2, 5)).collect();
+        if check(&program, &regs).is_some() {
+            let program = minimise(program, |program| check(program, &regs).is_some());
+            let case = Snapshot {
+                regs: regs.clone(),
+                pos: 0,
+                instructions: program.clone(),
+            };
+            panic!(
+                "backends disagree when starting with registers {regs:?}: {}\n{}\nsave as JSON to replay in the debugger: {}",
+                check(&program, &regs).unwrap(),
+                disassemble(&program, None),
+                JsonValue::from(&case).stringify().unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn minimises_mismatches() {
+    use Instruction::*;
+    use Operand::*;
+
+    // a stand-in for a backend bug: any program that toggles something disagrees.
+    let is_failing = |program: &Program| program.iter().any(|i| matches!(i, Tgl(_)));
+    let program = vec![Inc(Reg(0)), Tgl(Imm(3)), Cpy(Imm(2), Reg(1)), Out(Reg(1))];
+    assert!(is_failing(&program));
+
+    let program = minimise(program, is_failing);
+    assert_eq!(program, vec![Tgl(Imm(0))]);
+}
@@ -0,0 +1,275 @@
+//! JSON (de)serialisation of programs and machine states, so that snapshots and failing fuzz
+//! cases can be stored under `data/` and replayed in tests or the debugger.
+//!
+//! Instructions are stored as their assembunny source, e.g. `"cpy 1 a"`.
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::{fs, io};
+
+use tinyjson::JsonValue;
+
+use super::{Instruction, MAX_REGISTERS, Machine, Snapshot, State, Word, register_count};
+
+/// Largest magnitude a JSON number holds exactly; larger register values are stored as strings.
+const MAX_EXACT: i64 = 1 << 53;
+
+fn word_to_json<T: Word>(value: T) -> JsonValue {
+    let s = value.to_string();
+    match s.parse::<i64>() {
+        Ok(v) if v.unsigned_abs() <= MAX_EXACT as u64 => JsonValue::Number(v as f64),
+        _ => JsonValue::String(s),
+    }
+}
+
+/// Reads a register value, or `None` if it is not an integer or does not fit in `T`.
+fn word_from_json<T: Word>(value: &JsonValue) -> Option<T> {
+    match value {
+        JsonValue::Number(v) if v.fract() == 0.0 && v.abs() <= MAX_EXACT as f64 => {
+            T::try_from(*v as i64).ok()
+        }
+        JsonValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+impl From<&Instruction> for JsonValue {
+    fn from(value: &Instruction) -> Self {
+        JsonValue::String(value.to_string())
+    }
+}
+
+impl TryFrom<&JsonValue> for Instruction {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let source = value
+            .get::<String>()
+            .ok_or("Expected instruction to be a string.")?;
+        Instruction::from_str(source).map_err(|e| format!("Invalid instruction `{source}`: {e}"))
+    }
+}
+
+impl<T: Word> From<&Snapshot<T>> for JsonValue {
+    fn from(value: &Snapshot<T>) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "regs".into(),
+            JsonValue::Array(value.regs.iter().copied().map(word_to_json).collect()),
+        );
+        map.insert("pos".into(), JsonValue::Number(value.pos as f64));
+        map.insert(
+            "instructions".into(),
+            JsonValue::Array(value.instructions.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl<T: Word> TryFrom<&JsonValue> for Snapshot<T> {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected snapshot to be a JSON object.")?;
+
+        let regs = json
+            .get("regs")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .and_then(|regs| regs.iter().map(word_from_json).collect::<Option<Vec<T>>>())
+            .ok_or("Expected snapshot.regs to be an array of integers.")?;
+
+        let pos = json
+            .get("pos")
+            .and_then(|v| v.get::<f64>())
+            .filter(|pos| pos.fract() == 0.0)
+            .ok_or("Expected snapshot.pos to be an integer.")?;
+
+        let instructions: Vec<Instruction> = json
+            .get("instructions")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected snapshot.instructions to be an array.")?
+            .iter()
+            .map(Instruction::try_from)
+            .collect::<Result<_, _>>()?;
+
+        if regs.len() > MAX_REGISTERS {
+            return Err(format!(
+                "Expected snapshot.regs to hold at most {MAX_REGISTERS} registers, found {}.",
+                regs.len()
+            ));
+        }
+        let needed = register_count(&instructions);
+        if regs.len() < needed {
+            return Err(format!(
+                "Expected snapshot.regs to hold the {needed} registers the program uses, found {}.",
+                regs.len()
+            ));
+        }
+
+        // a machine that has halted points just past its last instruction.
+        if *pos < 0.0 || *pos > instructions.len() as f64 {
+            return Err(format!(
+                "Expected snapshot.pos to lie between 0 and {}, found {pos}.",
+                instructions.len()
+            ));
+        }
+
+        Ok(Snapshot {
+            regs,
+            pos: *pos as i64,
+            instructions,
+        })
+    }
+}
+
+impl<T: Word> Snapshot<T> {
+    /// Dehydrate the snapshot to a JSON file.
+    pub fn store_file(&self, path: &str) -> Result<(), io::Error> {
+        let json = JsonValue::from(self);
+        let mut file = fs::File::create(path)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate a snapshot from a JSON file.
+    pub fn read_file(path: &str) -> Result<Self, String> {
+        let s = fs::read_to_string(path).map_err(|e| format!("Failed to read \"{path}\": {e}"))?;
+        let json = JsonValue::from_str(&s).or(Err("not valid JSON file."))?;
+        Snapshot::try_from(&json)
+    }
+}
+
+/// Serialises the [`Snapshot`] of the state, plus whether the optimizer is enabled. Tracing and
+/// profiling are not stored.
+impl<T: Word> From<&State<T>> for JsonValue {
+    fn from(value: &State<T>) -> Self {
+        let mut json = JsonValue::from(&value.snapshot());
+        if let JsonValue::Object(map) = &mut json {
+            map.insert(
+                "optimized".into(),
+                JsonValue::Boolean(value.macros.is_some()),
+            );
+        }
+        json
+    }
+}
+
+impl<T: Word> TryFrom<&JsonValue> for State<T> {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let snapshot = Snapshot::try_from(value)?;
+        let optimized = value
+            .get::<HashMap<String, JsonValue>>()
+            .and_then(|json| json.get("optimized"))
+            .map_or(Some(&false), |v| v.get::<bool>())
+            .ok_or("Expected state.optimized to be a boolean.")?;
+
+        let mut builder =
+            State::builder(snapshot.instructions.clone()).registers(snapshot.regs.len());
+        if *optimized {
+            builder = builder.optimize();
+        }
+        let mut state = builder.build();
+        state.restore(&snapshot);
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::tests::MULTIPLY;
+    use crate::assembunny::{Operand, parse_program};
+
+    #[test]
+    fn round_trips_instructions() {
+        for instruction in parse_program(MULTIPLY).unwrap() {
+            let json = JsonValue::from(&instruction);
+            assert_eq!(Instruction::try_from(&json), Ok(instruction));
+        }
+        assert_eq!(
+            JsonValue::from(&Instruction::Jnz(Operand::Imm(1), Operand::Reg(2)))
+                .stringify()
+                .unwrap(),
+            r#""jnz 1 c""#
+        );
+        assert!(Instruction::try_from(&JsonValue::String("jmp 2".into())).is_err());
+    }
+
+    #[test]
+    fn round_trips_states() {
+        let mut state = State::<i128>::builder(parse_program(MULTIPLY).unwrap())
+            .reg('a', 7)
+            .reg('e', i128::from(i64::MAX) * 4)
+            .optimize()
+            .build();
+        for _ in 0..12 {
+            state.step();
+        }
+
+        let json = JsonValue::from(&state).stringify().unwrap();
+        let mut restored = State::<i128>::try_from(&JsonValue::from_str(&json).unwrap()).unwrap();
+        assert_eq!(restored.snapshot(), state.snapshot());
+        assert!(json.contains(r#""36893488147419103228""#));
+
+        restored.run();
+        assert_eq!(restored.reg('a'), 49);
+    }
+
+    #[test]
+    fn stores_large_words_as_strings() {
+        assert_eq!(
+            word_to_json(1i64 << 53),
+            JsonValue::Number(9007199254740992.0)
+        );
+        assert_eq!(
+            word_to_json((1i64 << 53) + 1),
+            JsonValue::String("9007199254740993".into())
+        );
+        assert_eq!(
+            word_to_json(-(1i64 << 53)),
+            JsonValue::Number(-9007199254740992.0)
+        );
+
+        for value in [i64::MIN, i64::MAX] {
+            let json = word_to_json(value);
+            assert_eq!(json, JsonValue::String(value.to_string()));
+            assert_eq!(word_from_json::<i64>(&json), Some(value));
+        }
+        for value in [i128::from(i64::MIN), i128::from(i64::MIN) - 1, i128::MIN] {
+            let json = word_to_json(value);
+            assert_eq!(json, JsonValue::String(value.to_string()));
+            assert_eq!(word_from_json::<i128>(&json), Some(value));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_snapshots() {
+        for json in [
+            r#"{ "regs": [0, 0], "pos": 0 }"#,
+            r#"{ "regs": [0, 0, 0, 0], "pos": -5, "instructions": [] }"#,
+            r#"{ "regs": [0, 0, 0, 0], "pos": 2, "instructions": ["inc a"] }"#,
+            r#"{ "regs": [0, 0, 0, 0], "pos": 1e300, "instructions": ["inc a"] }"#,
+            r#"{ "regs": [0.5], "pos": 0, "instructions": [] }"#,
+            r#"{ "regs": [0, 0, 0, 2147483648], "pos": 0, "instructions": [] }"#,
+            r#"{ "regs": [0, 0, 0, "-2147483649"], "pos": 0, "instructions": [] }"#,
+            r#"{ "regs": [], "pos": 0, "instructions": ["inc 1 2"] }"#,
+            r#"{ "regs": [0, 0], "pos": 0, "instructions": ["inc d"] }"#,
+            r#"{ "regs": [0, 0, 0, 0], "pos": 0, "instructions": ["inc e"] }"#,
+            &format!(
+                r#"{{ "regs": {:?}, "pos": 0, "instructions": [] }}"#,
+                [0; 27]
+            ),
+        ] {
+            let json = JsonValue::from_str(json).unwrap();
+            assert!(Snapshot::<i32>::try_from(&json).is_err());
+        }
+
+        let json = r#"{ "regs": [0, 0, 0, 2147483648], "pos": 0, "instructions": [] }"#;
+        let snapshot = Snapshot::<i64>::try_from(&JsonValue::from_str(json).unwrap()).unwrap();
+        assert_eq!(snapshot.regs[3], 2147483648);
+    }
+}
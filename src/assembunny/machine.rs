@@ -0,0 +1,154 @@
+use hashbrown::HashSet;
+
+use super::{Instruction, RunResult, Step, Word, register_index};
+
+/// An assembunny execution backend.
+///
+/// Backends only need to provide single-stepping and access to their registers; running,
+/// step budgets, loop detection and output handling are shared.
+pub trait Machine {
+    /// The integer type held by the registers.
+    type Word: Word;
+
+    /// Creates a machine with zeroed registers, positioned at the first instruction.
+    fn new(instructions: Vec<Instruction>) -> Self
+    where
+        Self: Sized;
+
+    /// Executes the instruction at the program counter.
+    fn step(&mut self) -> Step<Self::Word>;
+
+    /// The program counter.
+    fn pos(&self) -> i64;
+
+    /// The register file, indexed like [`Operand::Reg`](super::Operand::Reg).
+    fn regs(&self) -> &[Self::Word];
+
+    fn regs_mut(&mut self) -> &mut [Self::Word];
+
+    /// The program as currently modified by `tgl`.
+    fn instructions(&self) -> &[Instruction];
+
+    /// Puts the machine back into the state captured by [`Machine::snapshot`], including the
+    /// toggled program.
+    fn restore(&mut self, snapshot: &Snapshot<Self::Word>);
+
+    /// Captures the registers, program counter and toggled program.
+    fn snapshot(&self) -> Snapshot<Self::Word> {
+        Snapshot {
+            regs: self.regs().to_vec(),
+            pos: self.pos(),
+            instructions: self.instructions().to_vec(),
+        }
+    }
+
+    /// Returns the value of the register with the given name, e.g. `machine.reg('a')`.
+    ///
+    /// Panics if the machine has no such register.
+    fn reg(&self, name: char) -> Self::Word {
+        match register_index(name).and_then(|r| self.regs().get(r)) {
+            Some(value) => *value,
+            None => panic!("Invalid register: {name}"),
+        }
+    }
+
+    /// Sets the value of the register with the given name.
+    ///
+    /// Panics if the machine has no such register.
+    fn set_reg(&mut self, name: char, value: Self::Word) {
+        match register_index(name).and_then(|r| self.regs_mut().get_mut(r)) {
+            Some(reg) => *reg = value,
+            None => panic!("Invalid register: {name}"),
+        }
+    }
+
+    /// Runs the program until the program counter leaves it.
+    fn run(&mut self) {
+        while self.step() != Step::Halted {}
+    }
+
+    /// Runs the program for at most `max_steps` steps, discarding any output.
+    ///
+    /// As long as no `tgl` has fired, the registers are recorded at every backward jump; seeing
+    /// the same position and registers twice means the program is stuck in a loop. A `tgl`
+    /// changes the program, so it resets the recorded states.
+    fn run_with_limit(&mut self, max_steps: u64) -> RunResult {
+        match run_limited(self, max_steps, false) {
+            Ok(_) => unreachable!("output is discarded"),
+            Err(result) => result,
+        }
+    }
+
+    /// Runs until the next value is transmitted by `out`, with the same step budget and loop
+    /// detection as [`Machine::run_with_limit`].
+    fn next_output(&mut self, max_steps: u64) -> Result<Self::Word, RunResult> {
+        run_limited(self, max_steps, true)
+    }
+
+    /// Lazily runs the program, yielding every value transmitted by `out`.
+    ///
+    /// The iterator ends when the program halts, so `machine.output().take(n)` stops after `n`
+    /// values even if the program would otherwise run forever.
+    fn output(&mut self) -> Output<'_, Self>
+    where
+        Self: Sized,
+    {
+        Output { machine: self }
+    }
+}
+
+/// Everything that determines how a machine continues: two machines with equal snapshots produce
+/// the same output from there on. Snapshots can be hashed to detect repeated states.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot<T = i32> {
+    pub regs: Vec<T>,
+    pub pos: i64,
+    pub instructions: Vec<Instruction>,
+}
+
+fn run_limited<M: Machine + ?Sized>(
+    machine: &mut M,
+    max_steps: u64,
+    stop_at_output: bool,
+) -> Result<M::Word, RunResult> {
+    let mut seen = HashSet::new();
+    for _ in 0..max_steps {
+        let pos = machine.pos();
+        let instruction = machine.instructions().get(pos as usize).copied();
+        match machine.step() {
+            Step::Halted => return Err(RunResult::Halted),
+            Step::Output(value) if stop_at_output => return Ok(value),
+            _ => {}
+        }
+        match instruction {
+            Some(Instruction::Tgl(_)) => seen.clear(),
+            Some(Instruction::Jnz(..))
+                if machine.pos() <= pos
+                    && !seen.insert((machine.pos(), machine.regs().to_vec())) =>
+            {
+                return Err(RunResult::LoopDetected);
+            }
+            _ => {}
+        }
+    }
+    Err(RunResult::BudgetExhausted)
+}
+
+/// Iterator over the values transmitted by a running [`Machine`], see [`Machine::output`].
+pub struct Output<'a, M: Machine> {
+    machine: &'a mut M,
+}
+
+impl<M: Machine> Iterator for Output<'_, M> {
+    type Item = M::Word;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.machine.step() {
+                Step::Continue => {}
+                Step::Output(value) => return Some(value),
+                Step::Halted => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,595 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::str::FromStr;
+
+pub mod cfg;
+pub mod compiled;
+pub mod disasm;
+#[cfg(test)]
+mod fuzz;
+mod json;
+mod machine;
+pub mod optimizer;
+mod parser;
+pub mod symbolic;
+pub mod trace;
+
+pub use compiled::Compiled;
+pub use machine::{Machine, Output, Snapshot};
+pub use parser::{ParseError, parse_program};
+
+use optimizer::MacroOp;
+use trace::{Profiler, Tracer};
+
+/// Number of registers a machine has unless its program uses more, i.e. `a` to `d`.
+pub const DEFAULT_REGISTERS: usize = 4;
+
+/// Largest number of registers a machine can have, i.e. `a` to `z`.
+pub const MAX_REGISTERS: usize = 26;
+
+/// Registers are named by consecutive letters, starting at `a`.
+pub fn register_name(index: usize) -> char {
+    assert!(index < MAX_REGISTERS, "register {index} has no name");
+    (b'a' + index as u8) as char
+}
+
+/// The index of a register in the register file, if `name` is a valid register name.
+pub fn register_index(name: char) -> Option<usize> {
+    name.is_ascii_lowercase()
+        .then(|| (name as u8 - b'a') as usize)
+}
+
+/// The integer type held by the registers of a machine.
+pub trait Word:
+    Copy
+    + Default
+    + Eq
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + FromStr
+    + TryFrom<i64>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Converts a literal operand, truncating it if it does not fit.
+    fn from_i64(value: i64) -> Self;
+
+    /// Converts the value to a jump or `tgl` offset, truncating it if it does not fit.
+    fn to_i64(self) -> i64;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn from_i64(value: i64) -> Self {
+                    value as $t
+                }
+
+                #[inline]
+                fn to_i64(self) -> i64 {
+                    self as i64
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(i32, i64, i128);
+
+/// An instruction argument, resolved once at parse time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    /// Index into the register file.
+    Reg(usize),
+    /// Literal integer value.
+    Imm(i64),
+}
+
+impl Operand {
+    /// Parses a register name or an integer literal.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && let Some(r) = register_index(c)
+        {
+            return Some(Operand::Reg(r));
+        }
+        s.parse().ok().map(Operand::Imm)
+    }
+
+    /// Reads the operand from a register file.
+    #[inline]
+    pub fn value<T: Word>(&self, regs: &[T]) -> T {
+        match *self {
+            Operand::Reg(r) => regs[r],
+            Operand::Imm(v) => T::from_i64(v),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Reg(r) => write!(f, "{}", register_name(*r)),
+            Operand::Imm(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Cpy(Operand, Operand),
+    Inc(Operand),
+    Dec(Operand),
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+}
+
+impl Instruction {
+    /// The arguments of this instruction, in source order.
+    pub fn operands(&self) -> Vec<Operand> {
+        match *self {
+            Instruction::Cpy(a, b) | Instruction::Jnz(a, b) => vec![a, b],
+            Instruction::Inc(a)
+            | Instruction::Dec(a)
+            | Instruction::Tgl(a)
+            | Instruction::Out(a) => vec![a],
+        }
+    }
+
+    /// The assembunny mnemonic, e.g. `cpy`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::Cpy(..) => "cpy",
+            Instruction::Inc(_) => "inc",
+            Instruction::Dec(_) => "dec",
+            Instruction::Jnz(..) => "jnz",
+            Instruction::Tgl(_) => "tgl",
+            Instruction::Out(_) => "out",
+        }
+    }
+}
+
+/// Formats the instruction as assembunny source, which parses back to the same instruction.
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())?;
+        for operand in self.operands() {
+            write!(f, " {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed assembunny program.
+pub type Program = Vec<Instruction>;
+
+/// Number of registers needed to run `instructions`: the default set, or more if the program
+/// refers to registers beyond it.
+pub fn register_count(instructions: &[Instruction]) -> usize {
+    instructions
+        .iter()
+        .flat_map(Instruction::operands)
+        .filter_map(|operand| match operand {
+            Operand::Reg(r) => Some(r + 1),
+            Operand::Imm(_) => None,
+        })
+        .fold(DEFAULT_REGISTERS, usize::max)
+}
+
+/// The outcome of executing a single instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step<T = i32> {
+    Continue,
+    /// An `out` instruction transmitted a value.
+    Output(T),
+    /// The program counter left the program.
+    Halted,
+}
+
+/// Why [`Machine::run_with_limit`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunResult {
+    /// The program counter left the program.
+    Halted,
+    /// The step budget ran out before the program halted.
+    BudgetExhausted,
+    /// The machine returned to an earlier state, so it will never halt.
+    LoopDetected,
+}
+
+/// The reference interpreter, with optional peephole optimisation and instrumentation.
+pub struct State<T: Word = i32> {
+    pub regs: Vec<T>,
+
+    pub pos: i64,
+    pub instructions: Vec<Instruction>,
+    pub debug: bool,
+
+    /// Loop idioms to execute in one go, indexed by the position they start at.
+    macros: Option<Vec<Option<MacroOp>>>,
+    tracer: Option<Tracer>,
+    profiler: Option<Profiler>,
+}
+
+/// Configures the registers and options of a [`State`] before it starts running.
+///
+/// ```ignore
+/// let state = State::<i64>::builder(instructions).reg('a', 12).optimize().build();
+/// ```
+pub struct StateBuilder<T: Word = i32> {
+    instructions: Vec<Instruction>,
+    registers: usize,
+    values: Vec<(usize, T)>,
+    optimize: bool,
+}
+
+impl<T: Word> StateBuilder<T> {
+    /// Sets the initial value of a register, adding it to the register file if needed.
+    pub fn reg(mut self, name: char, value: T) -> Self {
+        let index = register_index(name).unwrap_or_else(|| panic!("Invalid register: {name}"));
+        self.registers = self.registers.max(index + 1);
+        self.values.push((index, value));
+        self
+    }
+
+    /// Sets the number of registers, at most [`MAX_REGISTERS`]. The register file still grows
+    /// to fit the program.
+    pub fn registers(mut self, count: usize) -> Self {
+        assert!(
+            count <= MAX_REGISTERS,
+            "a machine has at most {MAX_REGISTERS} registers, not {count}"
+        );
+        self.registers = count;
+        self
+    }
+
+    /// Enables the peephole optimizer, see [`State::optimize`].
+    pub fn optimize(mut self) -> Self {
+        self.optimize = true;
+        self
+    }
+
+    pub fn build(self) -> State<T> {
+        let registers = self.registers.max(register_count(&self.instructions));
+        let mut state = State {
+            regs: vec![T::ZERO; registers],
+
+            pos: 0,
+            instructions: self.instructions,
+            debug: false,
+
+            macros: None,
+            tracer: None,
+            profiler: None,
+        };
+        for (index, value) in self.values {
+            state.regs[index] = value;
+        }
+        if self.optimize {
+            state.optimize();
+        }
+        state
+    }
+}
+
+pub fn toggle_instruction(instruction: &Instruction) -> Instruction {
+    match *instruction {
+        Instruction::Cpy(a, b) => Instruction::Jnz(a, b),
+        Instruction::Inc(a) => Instruction::Dec(a),
+        Instruction::Dec(a) => Instruction::Inc(a),
+        Instruction::Jnz(a, b) => Instruction::Cpy(a, b),
+        Instruction::Tgl(a) | Instruction::Out(a) => Instruction::Inc(a),
+    }
+}
+
+impl<T: Word> State<T> {
+    /// Starts configuring a machine that runs `instructions`.
+    pub fn builder(instructions: Vec<Instruction>) -> StateBuilder<T> {
+        StateBuilder {
+            instructions,
+            registers: DEFAULT_REGISTERS,
+            values: vec![],
+            optimize: false,
+        }
+    }
+
+    pub fn new_c(instructions: Vec<Instruction>, c: T) -> Self {
+        Self::builder(instructions).reg('c', c).build()
+    }
+
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Self::builder(instructions).build()
+    }
+
+    /// Writes a line to `writer` for every executed instruction, see [`Tracer`].
+    pub fn trace_to(&mut self, writer: impl std::io::Write + Send + Sync + 'static) {
+        self.tracer = Some(Tracer::new(writer));
+    }
+
+    /// Starts counting how often each instruction is executed.
+    pub fn enable_profiler(&mut self) {
+        self.profiler = Some(Profiler::new(self.instructions.len()));
+    }
+
+    /// The hit counts collected since [`State::enable_profiler`] was called.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Enables the peephole optimizer, which runs add and multiply loops as single operations.
+    pub fn optimize(&mut self) {
+        self.macros = Some(optimizer::optimize(&self.instructions));
+    }
+
+    pub fn log(&self, msg: String) {
+        if self.debug {
+            println!("{}", msg);
+        }
+    }
+
+    #[inline]
+    fn value(&self, operand: Operand) -> T {
+        operand.value(&self.regs)
+    }
+
+    #[inline]
+    fn execute(&mut self) -> Step<T> {
+        let Some(&instruction) = self.instructions.get(self.pos as usize) else {
+            return Step::Halted;
+        };
+        if let Some(op) = self.macros.as_ref().and_then(|m| m[self.pos as usize])
+            && op.apply(&mut self.regs)
+        {
+            self.pos += op.span() as i64;
+            return Step::Continue;
+        }
+        match instruction {
+            Instruction::Cpy(v, Operand::Reg(d)) => {
+                self.regs[d] = self.value(v);
+                self.pos += 1;
+            }
+            Instruction::Inc(Operand::Reg(r)) => {
+                self.regs[r] += T::ONE;
+                self.pos += 1;
+            }
+            Instruction::Dec(Operand::Reg(r)) => {
+                self.regs[r] -= T::ONE;
+                self.pos += 1;
+            }
+            Instruction::Jnz(s, d) => {
+                if self.value(s) != T::ZERO {
+                    self.pos += self.value(d).to_i64();
+                } else {
+                    self.pos += 1;
+                }
+            }
+            Instruction::Tgl(dist) => {
+                let index_to_modify = self.pos + self.value(dist).to_i64();
+
+                if let Some(instruction) = self.instructions.get_mut(index_to_modify as usize) {
+                    *instruction = toggle_instruction(instruction);
+                    if self.debug {
+                        println!("new instruction: {instruction}");
+                    }
+                    if let Some(macros) = self.macros.as_mut() {
+                        optimizer::reoptimize(&self.instructions, macros, index_to_modify as usize);
+                    }
+                }
+                self.pos += 1;
+            }
+            Instruction::Out(v) => {
+                self.pos += 1;
+                return Step::Output(self.value(v));
+            }
+            // toggling can produce instructions that write to an immediate; these are skipped.
+            Instruction::Cpy(_, Operand::Imm(_))
+            | Instruction::Inc(Operand::Imm(_))
+            | Instruction::Dec(Operand::Imm(_)) => {
+                self.pos += 1;
+            }
+        }
+        Step::Continue
+    }
+}
+
+/// Clones the registers, program and options, so a machine can be set up once and then forked,
+/// e.g. across rayon workers. The clone does not trace, as the tracer's writer can't be shared.
+impl<T: Word> Clone for State<T> {
+    fn clone(&self) -> Self {
+        Self {
+            regs: self.regs.clone(),
+
+            pos: self.pos,
+            instructions: self.instructions.clone(),
+            debug: self.debug,
+
+            macros: self.macros.clone(),
+            tracer: None,
+            profiler: self.profiler.clone(),
+        }
+    }
+}
+
+impl<T: Word> Machine for State<T> {
+    type Word = T;
+
+    fn new(instructions: Vec<Instruction>) -> Self {
+        State::new(instructions)
+    }
+
+    fn restore(&mut self, snapshot: &Snapshot<T>) {
+        self.regs.clone_from(&snapshot.regs);
+        self.pos = snapshot.pos;
+        self.instructions.clone_from(&snapshot.instructions);
+        if self.macros.is_some() {
+            self.optimize();
+        }
+    }
+
+    fn step(&mut self) -> Step<T> {
+        if self.tracer.is_none() && self.profiler.is_none() {
+            return self.execute();
+        }
+
+        let pos = self.pos;
+        let before = self.regs.clone();
+        let instruction = self.instructions.get(pos as usize).copied();
+        let result = self.execute();
+        if let Some(instruction) = instruction {
+            if let Some(profiler) = self.profiler.as_mut() {
+                profiler.hit(pos as usize);
+            }
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.record(pos, &instruction, &before, &self.regs);
+            }
+        }
+        result
+    }
+
+    fn pos(&self) -> i64 {
+        self.pos
+    }
+
+    fn regs(&self) -> &[T] {
+        &self.regs
+    }
+
+    fn regs_mut(&mut self) -> &mut [T] {
+        &mut self.regs
+    }
+
+    fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A made-up program in the style of day 23: a multiply loop leaves `a * (a - 1)` in `a`, then
+    /// an add loop adds 7, unless the `tgl` rewrites it first.
+    pub(super) const MULTIPLY: &str = "cpy a b
+dec b
+cpy a d
+cpy 0 a
+cpy b c
+inc a
+dec c
+jnz c -2
+dec d
+jnz d -5
+tgl b
+cpy 7 c
+inc a
+dec c
+jnz c -2";
+
+    #[test]
+    fn output_is_lazy() {
+        let instructions = parse_program("out a\ninc a\njnz 1 -2").unwrap();
+        let mut state: State = State::new(instructions);
+
+        assert_eq!(state.output().take(4).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(state.reg('a'), 3);
+    }
+
+    #[test]
+    fn toggles_out_to_inc() {
+        let instructions = parse_program("tgl 1\nout a").unwrap();
+        let mut state: State = State::new(instructions);
+        state.run();
+
+        assert_eq!(state.instructions[1], Instruction::Inc(Operand::Reg(0)));
+        assert_eq!(state.reg('a'), 1);
+    }
+
+    #[test]
+    fn builder_sizes_register_file() {
+        let instructions = parse_program("cpy 2147483647 a\ninc a\ninc f").unwrap();
+        let mut state = State::<i64>::builder(instructions).reg('h', 5).build();
+        state.run();
+
+        assert_eq!(state.regs, vec![2147483648, 0, 0, 0, 0, 1, 0, 5]);
+        assert_eq!(state.reg('a'), 1 << 31);
+        assert_eq!(register_name(7), 'h');
+    }
+
+    #[test]
+    #[should_panic(expected = "a machine has at most 26 registers, not 27")]
+    fn builder_rejects_too_many_registers() {
+        let _ = State::<i32>::builder(vec![]).registers(27);
+    }
+
+    #[test]
+    fn run_with_limit() {
+        let instructions = parse_program("cpy 3 a\ndec a\njnz a -1").unwrap();
+        assert_eq!(
+            State::<i32>::new(instructions.clone()).run_with_limit(100),
+            RunResult::Halted
+        );
+        assert_eq!(
+            State::<i32>::new(instructions).run_with_limit(4),
+            RunResult::BudgetExhausted
+        );
+
+        let instructions = parse_program("inc a\ncpy 0 a\njnz 1 -1").unwrap();
+        assert_eq!(
+            State::<i32>::new(instructions).run_with_limit(100),
+            RunResult::LoopDetected
+        );
+
+        // the jump is toggled into a (skipped) `cpy` before it is reached.
+        let instructions = parse_program("cpy 2 b\ntgl 1\njnz 1 -1").unwrap();
+        assert_eq!(
+            State::<i32>::new(instructions).run_with_limit(100),
+            RunResult::Halted
+        );
+    }
+
+    #[test]
+    fn snapshots_restore_and_fork() {
+        use rayon::prelude::*;
+
+        let instructions = parse_program("cpy 2 b\ntgl b\ninc a\ndec b\njnz b -2\ninc a").unwrap();
+        let mut state: State = State::new(instructions);
+        state.run_with_limit(2);
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.pos, 2);
+        assert_eq!(snapshot.instructions[3], Instruction::Inc(Operand::Reg(1)));
+
+        state.run_with_limit(3);
+        assert_ne!(state.snapshot(), snapshot);
+        state.restore(&snapshot);
+        assert_eq!(state.snapshot(), snapshot);
+
+        let results: Vec<i32> = (0..4)
+            .into_par_iter()
+            .map(|a| {
+                let mut fork = state.clone();
+                fork.set_reg('a', a);
+                fork.set_reg('b', -2);
+                fork.run();
+                fork.reg('a')
+            })
+            .collect();
+        assert_eq!(results, vec![3, 4, 5, 6]);
+    }
+}
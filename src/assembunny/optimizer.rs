@@ -0,0 +1,186 @@
+//! Peephole optimisation of the add and multiply loops that assembunny programs use in place of
+//! arithmetic instructions.
+use super::{Instruction, Operand, Word};
+
+/// The longest instruction sequence replaced by a [`MacroOp`].
+pub const MAX_MACRO_LEN: usize = 6;
+
+/// A loop idiom that is executed as a single operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MacroOp {
+    /// `inc dst` / `dec src` / `jnz src -2` (in either order of the first two):
+    /// adds `src` to `dst` and clears `src`.
+    Add { dst: usize, src: usize },
+    /// `cpy factor tmp` / `inc dst` / `dec tmp` / `jnz tmp -2` / `dec count` / `jnz count -5`:
+    /// adds `factor * count` to `dst` and clears `tmp` and `count`.
+    Mul {
+        dst: usize,
+        factor: Operand,
+        tmp: usize,
+        count: usize,
+    },
+}
+
+impl MacroOp {
+    /// Number of instructions the operation replaces.
+    pub fn span(&self) -> usize {
+        match self {
+            MacroOp::Add { .. } => 3,
+            MacroOp::Mul { .. } => 6,
+        }
+    }
+
+    /// Executes the operation on a register file, returning `false` (and leaving the registers
+    /// untouched) when the loop would not terminate normally, i.e. its counter is not positive.
+    /// The caller should then fall back to interpreting the instructions one by one.
+    pub fn apply<T: Word>(&self, regs: &mut [T]) -> bool {
+        match *self {
+            MacroOp::Add { dst, src } => {
+                if regs[src] <= T::ZERO {
+                    return false;
+                }
+                let src_value = regs[src];
+                regs[dst] += src_value;
+                regs[src] = T::ZERO;
+            }
+            MacroOp::Mul {
+                dst,
+                factor,
+                tmp,
+                count,
+            } => {
+                let factor = factor.value(regs);
+                if factor <= T::ZERO || regs[count] <= T::ZERO {
+                    return false;
+                }
+                let product = factor * regs[count];
+                regs[dst] += product;
+                regs[tmp] = T::ZERO;
+                regs[count] = T::ZERO;
+            }
+        }
+        true
+    }
+}
+
+/// Finds the macro operation for every instruction that starts a recognised loop.
+///
+/// Jumps into the middle of a loop are unaffected, as only the first instruction is replaced.
+pub fn optimize(instructions: &[Instruction]) -> Vec<Option<MacroOp>> {
+    (0..instructions.len())
+        .map(|pos| find_macro(instructions, pos))
+        .collect()
+}
+
+/// Re-optimises the instructions around `changed`, e.g. after it was toggled by `tgl`.
+pub fn reoptimize(instructions: &[Instruction], macros: &mut [Option<MacroOp>], changed: usize) {
+    let start = changed.saturating_sub(MAX_MACRO_LEN - 1);
+    for pos in start..=changed.min(macros.len().saturating_sub(1)) {
+        macros[pos] = find_macro(instructions, pos);
+    }
+}
+
+/// Recognises a loop idiom starting at `pos`.
+pub fn find_macro(instructions: &[Instruction], pos: usize) -> Option<MacroOp> {
+    use Instruction::*;
+    use Operand::*;
+
+    match instructions.get(pos..)? {
+        [
+            Cpy(factor, Reg(tmp)),
+            Inc(Reg(dst)),
+            Dec(Reg(tmp2)),
+            Jnz(Reg(tmp3), Imm(-2)),
+            Dec(Reg(count)),
+            Jnz(Reg(count2), Imm(-5)),
+            ..,
+        ] if tmp == tmp2
+            && tmp == tmp3
+            && count == count2
+            && distinct(&[*dst, *tmp, *count])
+            && !matches!(factor, Reg(r) if [*dst, *tmp, *count].contains(r)) =>
+        {
+            Some(MacroOp::Mul {
+                dst: *dst,
+                factor: *factor,
+                tmp: *tmp,
+                count: *count,
+            })
+        }
+        [Inc(Reg(dst)), Dec(Reg(src)), Jnz(Reg(src2), Imm(-2)), ..]
+        | [Dec(Reg(src)), Inc(Reg(dst)), Jnz(Reg(src2), Imm(-2)), ..]
+            if src == src2 && dst != src =>
+        {
+            Some(MacroOp::Add {
+                dst: *dst,
+                src: *src,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn distinct(regs: &[usize]) -> bool {
+    regs.iter()
+        .enumerate()
+        .all(|(i, r)| !regs[i + 1..].contains(r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::tests::MULTIPLY;
+    use crate::assembunny::{Machine, State, parse_program};
+
+    #[test]
+    fn recognises_loops() {
+        let macros = optimize(&parse_program(MULTIPLY).unwrap());
+
+        assert_eq!(
+            macros[4],
+            Some(MacroOp::Mul {
+                dst: 0,
+                factor: Operand::Reg(1),
+                tmp: 2,
+                count: 3
+            })
+        );
+        assert_eq!(macros[5], Some(MacroOp::Add { dst: 0, src: 2 }));
+        assert_eq!(macros[12], Some(MacroOp::Add { dst: 0, src: 2 }));
+        assert_eq!(macros.iter().flatten().count(), 3);
+    }
+
+    #[test]
+    fn matches_interpreter_across_toggles() {
+        let instructions = parse_program(MULTIPLY).unwrap();
+
+        // 3 toggles `inc a` in the add loop, 5 its `jnz`, and 12 lands outside the program.
+        for (a, expected) in [(3, -1), (5, 21), (12, 139)] {
+            let mut plain: State = State::new(instructions.clone());
+            plain.set_reg('a', a);
+            plain.run();
+
+            let mut optimized: State = State::new(instructions.clone());
+            optimized.optimize();
+            optimized.set_reg('a', a);
+            optimized.run();
+
+            assert_eq!(optimized.regs, plain.regs);
+            assert_eq!(optimized.instructions, plain.instructions);
+            assert_eq!(optimized.reg('a'), expected);
+        }
+    }
+
+    #[test]
+    fn falls_back_for_non_positive_counters() {
+        let mut state: State =
+            State::new(parse_program("cpy 0 b\ninc a\ndec b\njnz b -2").unwrap());
+        state.optimize();
+        state.regs[1] = -3;
+        state.pos = 1;
+        state.step();
+
+        assert_eq!(state.pos, 2);
+        assert_eq!(state.reg('a'), 1);
+    }
+}
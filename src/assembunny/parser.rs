@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::{Instruction, Operand, Program};
+
+const EXPECTED_COMMAND: &str = "one of `cpy`, `inc`, `dec`, `jnz`, `tgl`, `out`";
+const EXPECTED_OPERAND: &str = "a register (`a`-`z`) or an integer";
+const EXPECTED_END: &str = "end of line";
+
+/// An error which can be returned when parsing assembunny source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number of the offending instruction.
+    pub line: usize,
+    /// 1-based column of the offending token.
+    pub column: usize,
+    /// The offending token, empty if the line ended early.
+    pub token: String,
+    /// Description of what the parser was looking for.
+    pub expected: &'static str,
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let found = if self.token.is_empty() {
+            "end of line".to_string()
+        } else {
+            format!("`{}`", self.token)
+        };
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, found
+        )
+    }
+}
+
+/// Parses a whole program, one instruction per line.
+///
+/// Blank lines, `; comments` and `label:` prefixes (as emitted by
+/// [`disassemble`](super::disasm::disassemble)) are ignored.
+pub fn parse_program(input: &str) -> Result<Program, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| parse_line(line, i + 1).transpose())
+        .collect()
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_line(s, 1)?.ok_or_else(|| ParseError {
+            line: 1,
+            column: 1,
+            token: String::new(),
+            expected: EXPECTED_COMMAND,
+        })
+    }
+}
+
+/// Parses a single line, returning `None` if it does not contain an instruction.
+fn parse_line(line: &str, line_no: usize) -> Result<Option<Instruction>, ParseError> {
+    let line = line.split(';').next().unwrap_or_default();
+    let mut tokens: Vec<(usize, &str)> = tokenize(line).collect();
+    if tokens
+        .first()
+        .is_some_and(|(_, token)| token.ends_with(':'))
+    {
+        tokens.remove(0);
+    }
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+    let end_of_line = (line.trim_end().chars().count() + 1, "");
+
+    let error = |(column, token): (usize, &str), expected| ParseError {
+        line: line_no,
+        column,
+        token: token.to_string(),
+        expected,
+    };
+    let operand = |i: usize| {
+        let token = tokens.get(i).copied().unwrap_or(end_of_line);
+        Operand::parse(token.1).ok_or_else(|| error(token, EXPECTED_OPERAND))
+    };
+
+    let command = tokens[0];
+    let instruction = match command.1 {
+        "cpy" => Instruction::Cpy(operand(1)?, operand(2)?),
+        "inc" => Instruction::Inc(operand(1)?),
+        "dec" => Instruction::Dec(operand(1)?),
+        "jnz" => Instruction::Jnz(operand(1)?, operand(2)?),
+        "tgl" => Instruction::Tgl(operand(1)?),
+        "out" => Instruction::Out(operand(1)?),
+        _ => return Err(error(command, EXPECTED_COMMAND)),
+    };
+
+    match tokens.get(instruction.operands().len() + 1) {
+        Some(&token) => Err(error(token, EXPECTED_END)),
+        None => Ok(Some(instruction)),
+    }
+}
+
+/// Splits a line on whitespace, yielding each token with its 1-based column.
+fn tokenize(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut column = 0;
+    line.split(char::is_whitespace).filter_map(move |token| {
+        let start = column + 1;
+        column += token.chars().count() + 1;
+        (!token.is_empty()).then_some((start, token))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_program() {
+        let program =
+            parse_program("cpy 41 a\n\nL1: inc a\n; comment\njnz a -2 ; -> L1\n").unwrap();
+
+        assert_eq!(
+            program,
+            vec![
+                Instruction::Cpy(Operand::Imm(41), Operand::Reg(0)),
+                Instruction::Inc(Operand::Reg(0)),
+                Instruction::Jnz(Operand::Reg(0), Operand::Imm(-2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let err = parse_program("inc a\ncpy 1 A").unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 7, "A"));
+        assert_eq!(err.expected, EXPECTED_OPERAND);
+
+        let err = parse_program("mul a b").unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (1, 1, "mul"));
+
+        let err = parse_program("jnz a").unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (6, ""));
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 6: expected a register (`a`-`z`) or an integer, found end of line"
+        );
+
+        let err = parse_program("dec b  c").unwrap_err();
+        assert_eq!((err.column, err.expected), (8, EXPECTED_END));
+
+        let err = parse_program("L1: inc 1x ; comment").unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (9, "1x"));
+    }
+}
@@ -0,0 +1,436 @@
+//! Symbolic execution: registers hold polynomials over named variables instead of numbers, so
+//! running a program tells what it computes rather than what it computes for one input.
+//!
+//! Control flow still has to be concrete. Straight-line code is evaluated exactly, and the add and
+//! multiply loops recognised by the [`optimizer`] are summarised in closed form, even when their
+//! counters are symbolic. Any other jump or `tgl` that depends on a symbolic value stops the
+//! evaluation.
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::optimizer::{self, MacroOp};
+use super::{
+    Instruction, Operand, Snapshot, Step, Word, register_count, register_index, toggle_instruction,
+};
+
+/// A polynomial with integer coefficients, kept in canonical form so that equal polynomials
+/// compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Expr {
+    /// The coefficient of every monomial, a monomial being the sorted list of its variables.
+    terms: BTreeMap<Vec<String>, i64>,
+}
+
+impl Expr {
+    pub fn constant(value: i64) -> Self {
+        let mut expr = Self::default();
+        expr.add_term(vec![], value);
+        expr
+    }
+
+    pub fn var(name: &str) -> Self {
+        let mut expr = Self::default();
+        expr.add_term(vec![name.to_string()], 1);
+        expr
+    }
+
+    /// The value of the expression, if it does not depend on any variable.
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.terms.len() {
+            0 => Some(0),
+            1 => self.terms.get(&vec![]).copied(),
+            _ => None,
+        }
+    }
+
+    /// Evaluates the expression, looking up variables with `value`.
+    pub fn eval(&self, value: impl Fn(&str) -> i64) -> i64 {
+        self.terms
+            .iter()
+            .map(|(monomial, coefficient)| {
+                monomial
+                    .iter()
+                    .map(|var| value(var))
+                    .fold(*coefficient, |product, v| product * v)
+            })
+            .sum()
+    }
+
+    fn add_term(&mut self, monomial: Vec<String>, coefficient: i64) {
+        let sum = self.terms.get(&monomial).copied().unwrap_or(0) + coefficient;
+        if sum == 0 {
+            self.terms.remove(&monomial);
+        } else {
+            self.terms.insert(monomial, sum);
+        }
+    }
+}
+
+impl From<i64> for Expr {
+    fn from(value: i64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(mut self, rhs: Expr) -> Expr {
+        for (monomial, coefficient) in rhs.terms {
+            self.add_term(monomial, coefficient);
+        }
+        self
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(mut self) -> Expr {
+        for coefficient in self.terms.values_mut() {
+            *coefficient = -*coefficient;
+        }
+        self
+    }
+}
+
+impl Sub for Expr {
+    type Output = Expr;
+
+    fn sub(self, rhs: Expr) -> Expr {
+        self + -rhs
+    }
+}
+
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, rhs: Expr) -> Expr {
+        let mut product = Expr::default();
+        for (a, x) in &self.terms {
+            for (b, y) in &rhs.terms {
+                let mut monomial: Vec<String> = a.iter().chain(b).cloned().collect();
+                monomial.sort_unstable();
+                product.add_term(monomial, x * y);
+            }
+        }
+        product
+    }
+}
+
+/// Formats the polynomial highest degree first, e.g. `x^2*y - 3*x + 1`.
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return f.write_str("0");
+        }
+
+        let mut terms: Vec<_> = self.terms.iter().collect();
+        terms.sort_by_key(|(monomial, _)| std::cmp::Reverse(monomial.len()));
+        for (i, (monomial, &coefficient)) in terms.into_iter().enumerate() {
+            match (i, coefficient < 0) {
+                (0, true) => f.write_str("-")?,
+                (0, false) => {}
+                (_, true) => f.write_str(" - ")?,
+                (_, false) => f.write_str(" + ")?,
+            }
+
+            let mut factors = vec![];
+            if coefficient.abs() != 1 || monomial.is_empty() {
+                factors.push(coefficient.abs().to_string());
+            }
+            for (j, var) in monomial.iter().enumerate() {
+                if j > 0 && monomial[j - 1] == *var {
+                    continue;
+                }
+                match monomial[j..].iter().take_while(|v| *v == var).count() {
+                    1 => factors.push(var.clone()),
+                    power => factors.push(format!("{var}^{power}")),
+                }
+            }
+            f.write_str(&factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Why symbolic evaluation stopped before the program halted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolicError {
+    /// The `jnz` or `tgl` at `pos` depends on a symbolic value.
+    Undecidable { pos: i64, value: Expr },
+    /// The step budget ran out.
+    BudgetExhausted,
+}
+
+impl Display for SymbolicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolicError::Undecidable { pos, value } => {
+                write!(f, "instruction {pos} depends on the symbolic value {value}")
+            }
+            SymbolicError::BudgetExhausted => f.write_str("step budget exhausted"),
+        }
+    }
+}
+
+impl std::error::Error for SymbolicError {}
+
+/// An interpreter over [`Expr`] registers.
+///
+/// ```ignore
+/// let mut symbolic = Symbolic::new(instructions);
+/// symbolic.set_reg('a', Expr::var("x"));
+/// symbolic.run(1_000)?;
+/// println!("a = {}", symbolic.reg('a'));
+/// ```
+pub struct Symbolic {
+    pub regs: Vec<Expr>,
+    pub pos: i64,
+    pub instructions: Vec<Instruction>,
+    /// Values transmitted by `out` during [`Symbolic::run`].
+    pub output: Vec<Expr>,
+    /// Expressions assumed to be positive, because a loop counting them down was summarised.
+    /// The results only hold for inputs that satisfy every assumption.
+    pub assumptions: Vec<Expr>,
+
+    macros: Vec<Option<MacroOp>>,
+}
+
+impl Symbolic {
+    /// Creates a machine with all registers set to zero.
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Self {
+            regs: vec![Expr::default(); register_count(&instructions)],
+            pos: 0,
+            macros: optimizer::optimize(&instructions),
+            instructions,
+            output: vec![],
+            assumptions: vec![],
+        }
+    }
+
+    /// Continues from a concrete machine state, e.g. to find out what the rest of a program
+    /// computes from a register once the setup code has run.
+    pub fn from_snapshot<T: Word>(snapshot: &Snapshot<T>) -> Self {
+        let mut symbolic = Self::new(snapshot.instructions.clone());
+        symbolic.pos = snapshot.pos;
+        symbolic.regs = snapshot
+            .regs
+            .iter()
+            .map(|value| Expr::constant(value.to_i64()))
+            .collect();
+        symbolic
+    }
+
+    /// Returns the expression held by the register with the given name.
+    ///
+    /// Panics if the machine has no such register.
+    pub fn reg(&self, name: char) -> &Expr {
+        match register_index(name).and_then(|r| self.regs.get(r)) {
+            Some(value) => value,
+            None => panic!("Invalid register: {name}"),
+        }
+    }
+
+    /// Sets the register with the given name, typically to a variable.
+    ///
+    /// Panics if the machine has no such register.
+    pub fn set_reg(&mut self, name: char, value: Expr) {
+        match register_index(name).and_then(|r| self.regs.get_mut(r)) {
+            Some(reg) => *reg = value,
+            None => panic!("Invalid register: {name}"),
+        }
+    }
+
+    /// Runs the program until it halts, collecting its [`output`](Symbolic::output).
+    pub fn run(&mut self, max_steps: u64) -> Result<(), SymbolicError> {
+        for _ in 0..max_steps {
+            match self.step()? {
+                Step::Continue => {}
+                Step::Output(value) => self.output.push(value),
+                Step::Halted => return Ok(()),
+            }
+        }
+        Err(SymbolicError::BudgetExhausted)
+    }
+
+    /// Executes the instruction, or the loop summary, at the program counter.
+    pub fn step(&mut self) -> Result<Step<Expr>, SymbolicError> {
+        let Some(&instruction) = self.instructions.get(self.pos as usize) else {
+            return Ok(Step::Halted);
+        };
+        if let Some(op) = self.macros[self.pos as usize]
+            && self.apply(op)
+        {
+            self.pos += op.span() as i64;
+            return Ok(Step::Continue);
+        }
+
+        match instruction {
+            Instruction::Cpy(v, Operand::Reg(d)) => self.regs[d] = self.value(v),
+            Instruction::Inc(Operand::Reg(r)) => {
+                self.regs[r] = self.regs[r].clone() + Expr::constant(1);
+            }
+            Instruction::Dec(Operand::Reg(r)) => {
+                self.regs[r] = self.regs[r].clone() - Expr::constant(1);
+            }
+            Instruction::Jnz(s, d) => {
+                if self.concrete(s)? != 0 {
+                    self.pos += self.concrete(d)?;
+                    return Ok(Step::Continue);
+                }
+            }
+            Instruction::Tgl(dist) => {
+                let index_to_modify = self.pos + self.concrete(dist)?;
+                if let Some(instruction) = self.instructions.get_mut(index_to_modify as usize) {
+                    *instruction = toggle_instruction(instruction);
+                    optimizer::reoptimize(
+                        &self.instructions,
+                        &mut self.macros,
+                        index_to_modify as usize,
+                    );
+                }
+            }
+            Instruction::Out(v) => {
+                self.pos += 1;
+                return Ok(Step::Output(self.value(v)));
+            }
+            Instruction::Cpy(_, Operand::Imm(_))
+            | Instruction::Inc(Operand::Imm(_))
+            | Instruction::Dec(Operand::Imm(_)) => {}
+        }
+        self.pos += 1;
+        Ok(Step::Continue)
+    }
+
+    fn value(&self, operand: Operand) -> Expr {
+        match operand {
+            Operand::Reg(r) => self.regs[r].clone(),
+            Operand::Imm(v) => Expr::constant(v),
+        }
+    }
+
+    /// The value of an operand that decides control flow.
+    fn concrete(&self, operand: Operand) -> Result<i64, SymbolicError> {
+        let value = self.value(operand);
+        value.as_constant().ok_or(SymbolicError::Undecidable {
+            pos: self.pos,
+            value,
+        })
+    }
+
+    /// Summarises a loop like [`MacroOp::apply`], assuming that symbolic counters are positive.
+    fn apply(&mut self, op: MacroOp) -> bool {
+        match op {
+            MacroOp::Add { dst, src } => {
+                let count = self.regs[src].clone();
+                if !self.assume_positive(&[&count]) {
+                    return false;
+                }
+                self.regs[dst] = self.regs[dst].clone() + count;
+                self.regs[src] = Expr::default();
+            }
+            MacroOp::Mul {
+                dst,
+                factor,
+                tmp,
+                count,
+            } => {
+                let factor = self.value(factor);
+                let times = self.regs[count].clone();
+                if !self.assume_positive(&[&factor, &times]) {
+                    return false;
+                }
+                self.regs[dst] = self.regs[dst].clone() + factor * times;
+                self.regs[tmp] = Expr::default();
+                self.regs[count] = Expr::default();
+            }
+        }
+        true
+    }
+
+    /// Returns `false` if a counter is known not to be positive, otherwise records the
+    /// symbolic ones as assumptions.
+    fn assume_positive(&mut self, counters: &[&Expr]) -> bool {
+        if counters
+            .iter()
+            .any(|counter| counter.as_constant().is_some_and(|value| value <= 0))
+        {
+            return false;
+        }
+        self.assumptions.extend(
+            counters
+                .iter()
+                .filter(|counter| counter.as_constant().is_none())
+                .map(|&counter| counter.clone()),
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::tests::MULTIPLY;
+    use crate::assembunny::{Machine, State, parse_program};
+
+    #[test]
+    fn simplifies_expressions() {
+        let x = Expr::var("x");
+        let y = Expr::var("y");
+
+        let expr = (x.clone() + Expr::constant(1)) * (x.clone() - Expr::constant(1));
+        assert_eq!(expr.to_string(), "x^2 - 1");
+        assert_eq!(expr.eval(|_| 5), 24);
+
+        let expr = Expr::constant(-2) * x.clone() * y.clone() + y.clone() - y;
+        assert_eq!(expr.to_string(), "-2*x*y");
+        assert_eq!((x.clone() - x).as_constant(), Some(0));
+    }
+
+    #[test]
+    fn stops_at_symbolic_branches() {
+        let instructions =
+            parse_program(&crate::template::read_file("examples", crate::day!(25))).unwrap();
+        let mut symbolic = Symbolic::new(instructions);
+        symbolic.set_reg('a', Expr::var("x"));
+
+        assert_eq!(
+            symbolic.run(1_000),
+            Err(SymbolicError::Undecidable {
+                pos: 8,
+                value: Expr::var("x") + Expr::constant(12)
+            })
+        );
+        assert_eq!(symbolic.reg('d').to_string(), "x + 12");
+        assert!(symbolic.assumptions.is_empty());
+    }
+
+    #[test]
+    fn derives_multiply_formula() {
+        let instructions = parse_program(MULTIPLY).unwrap();
+
+        // up to the `tgl` that depends on the input.
+        let mut symbolic = Symbolic::new(instructions.clone());
+        symbolic.set_reg('a', Expr::var("x"));
+        let Err(SymbolicError::Undecidable { pos: 10, value }) = symbolic.run(1_000) else {
+            panic!("expected to stop at `tgl b`");
+        };
+        assert_eq!(value.to_string(), "x - 1");
+        assert_eq!(symbolic.reg('a').to_string(), "x^2 - x");
+        assert_eq!(symbolic.assumptions.len(), 2);
+
+        // what the program adds once the `tgl` has missed.
+        let mut state: State<i64> = State::builder(instructions).reg('a', 7).build();
+        while state.pos != 11 {
+            state.step();
+        }
+        let mut symbolic = Symbolic::from_snapshot(&state.snapshot());
+        symbolic.set_reg('a', Expr::var("x"));
+        assert_eq!(symbolic.run(1_000), Ok(()));
+        assert_eq!(symbolic.reg('a').to_string(), "x + 7");
+        assert_eq!(symbolic.reg('a').eval(|_| 42), 49);
+    }
+}
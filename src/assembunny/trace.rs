@@ -0,0 +1,119 @@
+//! Opt-in instrumentation for [`State`](super::State): an execution tracer and a per-instruction
+//! profiler.
+use std::fmt::Write as _;
+use std::io::Write;
+
+use super::{Instruction, Word};
+
+/// Writes one line per executed instruction: the step number, the position, the instruction and
+/// the registers before and after executing it.
+pub struct Tracer {
+    writer: Box<dyn Write + Send + Sync>,
+    step: u64,
+}
+
+impl Tracer {
+    pub fn new(writer: impl Write + Send + Sync + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+            step: 0,
+        }
+    }
+
+    pub fn record<T: Word>(
+        &mut self,
+        pos: i64,
+        instruction: &Instruction,
+        before: &[T],
+        after: &[T],
+    ) {
+        // tracing is a debugging aid, a failing writer should not abort the program.
+        let _ = writeln!(
+            self.writer,
+            "{:>8} {:>4}  {:<12} {:?} -> {:?}",
+            self.step,
+            pos,
+            instruction.to_string(),
+            before,
+            after
+        );
+        self.step += 1;
+    }
+}
+
+/// Counts how often each instruction was executed.
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    pub hits: Vec<u64>,
+}
+
+impl Profiler {
+    pub fn new(len: usize) -> Self {
+        Self { hits: vec![0; len] }
+    }
+
+    pub fn hit(&mut self, pos: usize) {
+        if let Some(hits) = self.hits.get_mut(pos) {
+            *hits += 1;
+        }
+    }
+
+    /// Renders the program with the hit count and share of executed instructions in front of
+    /// every line.
+    pub fn listing(&self, instructions: &[Instruction]) -> String {
+        let total = self.hits.iter().sum::<u64>().max(1);
+
+        let mut listing = String::new();
+        for (pos, instruction) in instructions.iter().enumerate() {
+            let hits = self.hits.get(pos).copied().unwrap_or(0);
+            let _ = writeln!(
+                listing,
+                "{:>12} {:>6.2}% {:>4}  {}",
+                hits,
+                hits as f64 * 100.0 / total as f64,
+                pos,
+                instruction
+            );
+        }
+        listing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::assembunny::{Machine, State, parse_program};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn traces_and_profiles() {
+        let buffer = Buffer::default();
+        let mut state: State = State::new(parse_program("cpy 2 a\ndec a\njnz a -1").unwrap());
+        state.trace_to(buffer.clone());
+        state.enable_profiler();
+        state.run();
+
+        let trace = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = trace.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].contains("[2, 0, 0, 0] -> [1, 0, 0, 0]"));
+
+        let profiler = state.profiler().unwrap();
+        assert_eq!(profiler.hits, vec![1, 2, 2]);
+        assert!(profiler.listing(&state.instructions).contains("40.00%"));
+    }
+}
@@ -1,89 +1,59 @@
 advent_of_code::solution!(1);
 
-use aoc_mine::Coord;
-
-#[derive(Copy, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
+use advent_of_code::{CardinalDirection, IPoint, Turn};
 
-use Direction::*;
 use hashbrown::HashSet;
 
-impl Direction {
-    fn new_direction(c: &str, direction: Self) -> Self {
-        if c == "R" {
-            match direction {
-                North => East,
-                East => South,
-                South => West,
-                West => North,
-            }
-        } else {
-            match direction {
-                North => West,
-                West => South,
-                South => East,
-                East => North,
-            }
-        }
-    }
+/// Splits an instruction like `R2` into its turn and distance.
+fn parse_instruction(instruction: &str) -> (Turn, u32) {
+    let (turn, count) = instruction.split_at(1);
+    let turn = Turn::try_from(turn.chars().next().unwrap())
+        .unwrap_or_else(|_| panic!("invalid turn {turn}"));
+    let count: u32 = count
+        .parse()
+        .unwrap_or_else(|_| panic!("could not parse {count}"));
+    (turn, count)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut coord: Coord<i32> = Coord(0, 0);
+    let mut coord: IPoint = IPoint::default();
 
-    let mut current_direction = Direction::North;
+    let mut current_direction = CardinalDirection::North;
     input
         .trim()
         .split(',')
         .map(|i| i.trim())
         .for_each(|instruction| {
-            let (direction, count) = instruction.split_at(1);
-            let count: u32 = count.parse().expect("could not parse {count}");
-
-            current_direction = Direction::new_direction(direction, current_direction);
-            (0..count).for_each(|_| match current_direction {
-                North => coord.move_up(),
-                East => coord.move_right(),
-                South => coord.move_down(),
-                West => coord.move_left(),
-            });
+            let (turn, count) = parse_instruction(instruction);
+
+            current_direction = current_direction.turn(turn);
+            (0..count).for_each(|_| coord = coord.step(current_direction));
         });
 
-    Some(coord.x().unsigned_abs() + coord.y().unsigned_abs())
+    Some(coord.manhattan(&IPoint::default()) as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut coord: Coord<i32> = Coord(0, 0);
-    let mut visited: HashSet<Coord<i32>> = HashSet::new();
+    let mut coord: IPoint = IPoint::default();
+    let mut visited: HashSet<IPoint> = HashSet::new();
     visited.insert(coord);
     let mut found = false;
 
-    let mut current_direction = Direction::North;
+    let mut current_direction = CardinalDirection::North;
     input
         .trim()
         .split(',')
         .map(|i| i.trim())
         .for_each(|instruction| {
             if !found {
-                let (direction, count) = instruction.split_at(1);
-                let count: u32 = count.parse().expect("could not parse {count}");
+                let (turn, count) = parse_instruction(instruction);
 
-                current_direction = Direction::new_direction(direction, current_direction);
+                current_direction = current_direction.turn(turn);
                 (0..count).for_each(|_| {
                     if found {
                         return;
                     }
-                    match current_direction {
-                        North => coord.move_up(),
-                        East => coord.move_right(),
-                        South => coord.move_down(),
-                        West => coord.move_left(),
-                    }
+                    coord = coord.step(current_direction);
 
                     if visited.contains(&coord) {
                         found = true;
@@ -94,7 +64,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             }
         });
 
-    Some(coord.x().unsigned_abs() + coord.y().unsigned_abs())
+    Some(coord.manhattan(&IPoint::default()) as u32)
 }
 
 #[cfg(test)]
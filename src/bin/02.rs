@@ -1,5 +1,7 @@
 use std::fmt;
 
+use advent_of_code::Direction;
+
 advent_of_code::solution!(2);
 
 #[derive(Clone, Copy, Debug)]
@@ -72,13 +74,14 @@ impl fmt::Display for KeypadTwo {
 impl Keypad {
     pub fn parse(start: Option<Keypad>, instructions: &str) -> Self {
         let mut val = start.unwrap_or(Keypad::Five);
-        let instructions: Vec<char> = instructions.chars().collect();
-        instructions.iter().for_each(|i| match i {
-            'U' => val = val.up(),
-            'D' => val = val.down(),
-            'L' => val = val.left(),
-            'R' => val = val.right(),
-            _ => panic!("Invalid instruction {i}"),
+        instructions.chars().for_each(|i| {
+            val = match Direction::try_from(i) {
+                Ok(Direction::Up) => val.up(),
+                Ok(Direction::Down) => val.down(),
+                Ok(Direction::Left) => val.left(),
+                Ok(Direction::Right) => val.right(),
+                Err(i) => panic!("Invalid instruction {i}"),
+            }
         });
 
         val
@@ -143,13 +146,14 @@ impl Keypad {
 impl KeypadTwo {
     pub fn parse(start: Option<KeypadTwo>, instructions: &str) -> Self {
         let mut val = start.unwrap_or(KeypadTwo::Five);
-        let instructions: Vec<char> = instructions.chars().collect();
-        instructions.iter().for_each(|i| match i {
-            'U' => val = val.up(),
-            'D' => val = val.down(),
-            'L' => val = val.left(),
-            'R' => val = val.right(),
-            _ => panic!("Invalid instruction {i}"),
+        instructions.chars().for_each(|i| {
+            val = match Direction::try_from(i) {
+                Ok(Direction::Up) => val.up(),
+                Ok(Direction::Down) => val.down(),
+                Ok(Direction::Left) => val.left(),
+                Ok(Direction::Right) => val.right(),
+                Err(i) => panic!("Invalid instruction {i}"),
+            }
         });
 
         val
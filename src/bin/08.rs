@@ -1,5 +1,6 @@
-advent_of_code::solution!(8);
+advent_of_code::solution!(8, params: Params);
 
+use advent_of_code::{Grid, Point, ocr};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -9,14 +10,13 @@ use nom::{
     IResult,
 };
 
-#[cfg(test)]
-const ROWS: u32 = 3;
-#[cfg(test)]
-const COLS: u32 = 7;
-#[cfg(not(test))]
-const ROWS: u32 = 6;
-#[cfg(not(test))]
-const COLS: u32 = 50;
+advent_of_code::params! {
+    /// The size of the screen.
+    pub struct Params {
+        cols: usize = 50,
+        rows: usize = 6,
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rect {
@@ -86,58 +86,35 @@ pub fn parse_input(input: &str) -> Vec<Instruction> {
     instructions
 }
 
-fn handle_rect(grid: &mut [bool], instruction: Rect) {
+fn handle_rect(grid: &mut Grid<bool>, instruction: Rect) {
     for x in 0..instruction.x {
         for y in 0..instruction.y {
-            let pos = (y * COLS + x) as usize;
-            grid[pos] = true;
+            grid[Point::from((x, y))] = true;
         }
     }
 }
 
-fn handle_row(grid: &mut [bool], instruction: RotateRow) {
-    let start = (instruction.y * COLS) as usize;
-    let end = ((instruction.y + 1) * COLS) as usize;
-    let mut row_slice = grid[start..end].to_vec();
-    row_slice.rotate_right(instruction.offset as usize);
-
-    grid[start..end].copy_from_slice(&row_slice);
+fn handle_row(grid: &mut Grid<bool>, instruction: RotateRow) {
+    grid.rotate_row(instruction.y as usize, instruction.offset as usize);
 }
 
-fn handle_column(grid: &mut [bool], instruction: RotateColumn) {
-    let mut col: Vec<bool> = Vec::new();
-
-    (0..ROWS).for_each(|row| {
-        col.push(*grid.get((row * COLS + instruction.x) as usize).unwrap());
-    });
-
-    col.rotate_right(instruction.offset as usize);
-
-    (0..ROWS).for_each(|row| {
-        let pos = (row * COLS + instruction.x) as usize;
-        let value = col.get(row as usize).unwrap();
-        grid[pos] = *value;
-    });
+fn handle_column(grid: &mut Grid<bool>, instruction: RotateColumn) {
+    grid.rotate_column(instruction.x as usize, instruction.offset as usize);
 }
 
-fn print_grid(grid: &[bool]) {
-    for row in 0..ROWS {
-        let start = (row * COLS) as usize;
-        let end = ((row + 1) * COLS) as usize;
-        let line = grid[start..end]
-            .iter()
-            .map(|v| match v {
-                true => '#',
-                false => ' ',
-            })
-            .collect::<String>();
-        println!("{line}");
-    }
+fn print_grid(grid: &Grid<bool>) {
+    print!(
+        "{}",
+        grid.render(|v| match v {
+            true => '#',
+            false => ' ',
+        })
+    );
     println!("---");
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut grid: Vec<bool> = vec![false; (ROWS * COLS) as usize];
+pub fn part_one(input: &str, params: &Params) -> Option<u32> {
+    let mut grid = Grid::new(params.cols, params.rows, false);
     let instructions = parse_input(input);
     // print_grid(&grid);
     for instruction in instructions {
@@ -148,11 +125,11 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
     }
 
-    Some(grid.iter().filter(|v| **v).count() as u32)
+    Some(grid.iter().filter(|(_, v)| **v).count() as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid: Vec<bool> = vec![false; (ROWS * COLS) as usize];
+pub fn part_two(input: &str, params: &Params) -> Option<String> {
+    let mut grid = Grid::new(params.cols, params.rows, false);
     let instructions = parse_input(input);
     // print_grid(&grid);
     for instruction in instructions {
@@ -163,8 +140,15 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     }
 
-    print_grid(&grid);
-    Some(1) // just so the solution shows as solved
+    match ocr::recognise(&grid) {
+        Ok(code) => Some(code),
+        Err(err) => {
+            // fall back to reading the screen by eye
+            eprintln!("Could not read the screen: {err}");
+            print_grid(&grid);
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -193,13 +177,20 @@ rotate column x=4 by 10";
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
         assert_eq!(result, Some(6));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1));
+        // the example screen is too small to hold letters
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
+        assert_eq!(result, None);
     }
 }
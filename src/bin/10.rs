@@ -2,7 +2,15 @@ use std::collections::VecDeque;
 
 use hashbrown::HashMap;
 
-advent_of_code::solution!(10);
+advent_of_code::solution!(10, params: Params);
+
+advent_of_code::params! {
+    /// The chips whose comparison part one asks about.
+    pub struct Params {
+        low: usize = 17,
+        high: usize = 61,
+    }
+}
 
 #[derive(Debug)]
 enum Target {
@@ -16,7 +24,7 @@ struct BotInstruction {
     high_target: Target,
 }
 
-fn solver(input: &str, exit_early: bool) -> (Option<u32>, HashMap<usize, usize>) {
+fn solver(input: &str, params: &Params, exit_early: bool) -> (Option<u32>, HashMap<usize, usize>) {
     let mut bot_instructions = HashMap::new();
     let mut bot_chips = HashMap::new();
     let mut outputs = HashMap::new();
@@ -65,7 +73,7 @@ fn solver(input: &str, exit_early: bool) -> (Option<u32>, HashMap<usize, usize>)
         chips.clear();
 
         // Check if this bot is responsible for comparing the target values
-        if low == 17 && high == 61 {
+        if low == params.low && high == params.high {
             responsible_bot = Some(bot);
             if exit_early {
                 break;
@@ -99,18 +107,18 @@ fn solver(input: &str, exit_early: bool) -> (Option<u32>, HashMap<usize, usize>)
     if let Some(bot) = responsible_bot {
         result = Some(bot as u32);
     } else {
-        println!("No bot compared 61 and 17.");
+        println!("No bot compared {} and {}.", params.high, params.low);
     }
 
     (result, outputs)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    solver(input, true).0
+pub fn part_one(input: &str, params: &Params) -> Option<u32> {
+    solver(input, params, true).0
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut outputs = solver(input, false).1;
+pub fn part_two(input: &str, params: &Params) -> Option<u32> {
+    let mut outputs = solver(input, params, false).1;
     let zero = *outputs.entry(0).or_default();
     let one = *outputs.entry(1).or_default();
     let two = *outputs.entry(2).or_default();
@@ -124,13 +132,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
+        assert_eq!(result, Some(2));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
         assert_eq!(result, Some(30));
     }
 }
@@ -2,8 +2,8 @@
 advent_of_code::solution!(11);
 use std::hash::{Hash, Hasher};
 
+use advent_of_code::search::bfs;
 use hashbrown::HashSet;
-use std::collections::VecDeque;
 
 #[derive(Clone, Eq, Debug)]
 struct State {
@@ -171,28 +171,9 @@ impl State {
 }
 
 fn solve(initial_state: State) -> usize {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-
-    queue.push_back((initial_state.clone(), 0)); // (State, Steps)
-    visited.insert(initial_state);
-
-    while let Some((current_state, steps)) = queue.pop_front() {
-        // Check if we've reached the goal
-        if current_state.is_goal() {
-            return steps;
-        }
-
-        // Generate valid next states
-        for next_state in current_state.next_states() {
-            if !visited.contains(&next_state) {
-                visited.insert(next_state.clone());
-                queue.push_back((next_state, steps + 1));
-            }
-        }
-    }
-
-    unreachable!("No solution found!");
+    bfs(initial_state, State::next_states, State::is_goal)
+        .expect("No solution found!")
+        .cost
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -1,4 +1,4 @@
-use advent_of_code::assembunny::{parse_line, Instruction, State};
+use advent_of_code::assembunny::{Compiled, Machine, parse_program};
 
 advent_of_code::solution!(12);
 
@@ -7,25 +7,28 @@ advent_of_code::solution!(12);
 // * `dec x` *decreases* the value of register `x` by one.
 // * `jnz x y` *jumps* to an instruction `y` away (positive means forward; negative means backward), but only if `x` is *not zero*.
 
-pub fn part_one(input: &str) -> Option<i32> {
-    let instructions: Vec<Instruction> = input.lines().map(parse_line).collect();
-    let mut state = State::new_c(instructions, 0);
-    state.run();
+/// Runs the program on the backend `M`, starting with `c` in register `c`.
+fn run<M: Machine<Word = i32>>(input: &str, c: i32) -> Option<i32> {
+    let instructions = parse_program(input).map_err(|e| eprintln!("{e}")).ok()?;
+    let mut machine = M::new(instructions);
+    machine.set_reg('c', c);
+    machine.run();
 
-    Some(state.a)
+    Some(machine.reg('a'))
 }
 
-pub fn part_two(input: &str) -> Option<i32> {
-    let instructions: Vec<Instruction> = input.lines().map(parse_line).collect();
-    let mut state = State::new_c(instructions, 1);
-    state.run();
+pub fn part_one(input: &str) -> Option<i32> {
+    run::<Compiled>(input, 0)
+}
 
-    Some(state.a)
+pub fn part_two(input: &str) -> Option<i32> {
+    run::<Compiled>(input, 1)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::assembunny::State;
 
     #[test]
     fn test_part_one() {
@@ -38,4 +41,17 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(42));
     }
+
+    #[test]
+    fn backends_agree() {
+        // `a` sums 5 + 4 + 3 + 2 + 1, then takes one off unless `c` is set.
+        let looping = "cpy 5 b\ncpy b d\ninc a\ndec d\njnz d -2\ndec b\njnz b -5\njnz c 2\ndec a";
+        let example = advent_of_code::template::read_file("examples", DAY);
+        for input in [looping, example.as_str()] {
+            for c in [0, 1] {
+                assert_eq!(run::<State>(input, c), run::<Compiled>(input, c));
+            }
+        }
+        assert_eq!(run::<State>(looping, 0), Some(14));
+    }
 }
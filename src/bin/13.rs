@@ -1,10 +1,17 @@
-use std::collections::VecDeque;
-
-use advent_of_code::Point;
-use hashbrown::HashSet;
-use pathfinding::prelude::bfs;
-
-advent_of_code::solution!(13);
+use advent_of_code::search::{bfs, reachable_within};
+use advent_of_code::{Bounds, Point};
+
+advent_of_code::solution!(13, params: Params);
+
+advent_of_code::params! {
+    pub struct Params {
+        /// The location part one walks to.
+        goal_x: u32 = 31,
+        goal_y: u32 = 39,
+        /// The number of steps part two may take.
+        max_steps: usize = 50,
+    }
+}
 
 trait IsWall {
     fn is_wall(&self, fave_number: u32) -> bool;
@@ -20,70 +27,39 @@ impl IsWall for Point {
     }
 }
 
-fn reachable_locations(start: Point, max_steps: usize, fave_number: u32) -> usize {
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-
-    queue.push_back((start, 0));
-
-    while let Some((current, steps)) = queue.pop_front() {
-        if steps > max_steps || !visited.insert(current) {
-            continue;
-        }
-
-        current
-            .udlr([0, u32::MAX, 0, u32::MAX])
-            .iter()
-            .filter(|p| !p.is_wall(fave_number))
-            .filter(|&p| !visited.contains(p))
-            .for_each(|&p| queue.push_back((p, steps + 1)));
-    }
-
-    visited.len()
+/// The locations next to `point` that are not walls.
+fn open_neighbors(point: &Point, fave_number: u32) -> impl Iterator<Item = Point> + use<> {
+    point
+        .neighbors4(Bounds::ALL)
+        .filter(move |p| !p.is_wall(fave_number))
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str, params: &Params) -> Option<usize> {
     let fave_number = input
         .trim_end()
         .parse::<u32>()
         .expect("Could not parse as number");
 
-    let goal = if fave_number == 10 {
-        Point::from((7u32, 4))
-    } else {
-        Point::from((31u32, 39))
-    };
+    let goal = Point::from((params.goal_x, params.goal_y));
 
     let start = Point::from((1usize, 1));
 
-    let result = bfs(
-        &start,
-        |p| {
-            p.udlr([0, u32::MAX, 0, u32::MAX])
-                .iter()
-                .filter(|p| !p.is_wall(fave_number))
-                .copied()
-                .collect::<Vec<Point>>()
-        },
-        |&p| p == goal,
-    )
-    .expect("could not complete BFS");
-    Some(result.len() - 1)
+    let result = bfs(start, |p| open_neighbors(p, fave_number), |&p| p == goal)
+        .expect("could not complete BFS");
+    Some(result.cost)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str, params: &Params) -> Option<usize> {
     let fave_number = input
         .trim_end()
         .parse::<u32>()
         .expect("Could not parse as number");
 
-    let max_steps = if fave_number == 10 { 10 } else { 50 };
-
     let start = Point::from((1usize, 1));
 
-    let result = reachable_locations(start, max_steps, fave_number);
+    let result = reachable_within(start, params.max_steps, |p| open_neighbors(p, fave_number));
 
-    Some(result)
+    Some(result.len())
 }
 
 #[cfg(test)]
@@ -92,13 +68,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
         assert_eq!(result, Some(11));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
         assert_eq!(result, Some(18));
     }
 }
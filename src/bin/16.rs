@@ -1,4 +1,12 @@
-advent_of_code::solution!(16);
+advent_of_code::solution!(16, params: Params);
+
+advent_of_code::params! {
+    /// The sizes of the disks to fill.
+    pub struct Params {
+        part_one_disk: usize = 272,
+        part_two_disk: usize = 35651584,
+    }
+}
 
 // - Call the data you have at this point "a".
 // - Make a copy of "a"; call this copy "b".
@@ -48,15 +56,13 @@ pub fn compute_checksum(input: &str) -> String {
     checksum_step(input, true)
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let disk_size = 272;
-    let randomized = add_random(disk_size, input.trim_end());
+pub fn part_one(input: &str, params: &Params) -> Option<String> {
+    let randomized = add_random(params.part_one_disk, input.trim_end());
     Some(compute_checksum(&randomized))
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    let disk_size = 35651584;
-    let randomized = add_random(disk_size, input.trim_end());
+pub fn part_two(input: &str, params: &Params) -> Option<String> {
+    let randomized = add_random(params.part_two_disk, input.trim_end());
     Some(compute_checksum(&randomized))
 }
 
@@ -83,11 +89,20 @@ mod tests {
         assert_eq!(randomized, "10000011110010000111".to_owned());
         let result = compute_checksum(&randomized);
         assert_eq!(result, "01100".to_owned());
+
+        let result = part_one(
+            &advent_of_code::template::read_file_part("examples", DAY, 1),
+            &advent_of_code::template::read_params_part("examples", DAY, 1),
+        );
+        assert_eq!(result, Some("01100".to_owned()));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_params("examples", DAY),
+        );
         assert_eq!(result, Some("01110011011000110".to_owned()));
     }
 }
@@ -1,8 +1,10 @@
-use std::collections::VecDeque;
+use advent_of_code::search::{bfs, longest_path};
+use advent_of_code::{Direction, Point};
 
 advent_of_code::solution!(17);
 
-// https://www.geeksforgeeks.org/breadth-first-search-or-bfs-for-a-graph/#bfs-from-a-given-source
+const START: Point = Point { x: 0, y: 0 };
+const VAULT: Point = Point { x: 3, y: 3 };
 
 fn is_open(c: char) -> bool {
     matches!(c, 'b'..='f')
@@ -19,92 +21,46 @@ fn get_doors(passcode: &str, path: &str) -> [bool; 4] {
     ]
 }
 
-fn bfs(passcode: &str) -> Option<String> {
-    let start = (0, 0, String::new());
-    let mut queue = VecDeque::new();
-    queue.push_back(start);
-
-    while let Some((x, y, path)) = queue.pop_front() {
-        if x == 3 && y == 3 {
-            return Some(path);
-        }
-
-        let doors = get_doors(passcode, &path);
-
-        if doors[0] && y > 0 {
-            queue.push_back((x, y - 1, format!("{}U", path)));
-        }
-        if doors[1] && y < 3 {
-            queue.push_back((x, y + 1, format!("{}D", path)));
-        }
-        if doors[2] && x > 0 {
-            queue.push_back((x - 1, y, format!("{}L", path)));
-        }
-        if doors[3] && x < 3 {
-            queue.push_back((x + 1, y, format!("{}R", path)));
-        }
-    }
-
-    None
-}
-
-fn dfs(passcode: &str, x: usize, y: usize, path: &str) -> Option<String> {
-    if x == 3 && y == 3 {
-        // If we reached the vault, return the path
-        return Some(path.to_string());
-    }
-
-    let doors = get_doors(passcode, path);
-    let mut longest_path = None;
-
-    if doors[0]
-        && y > 0
-        && let Some(new_path) = dfs(passcode, x, y - 1, &format!("{}U", path))
-    {
-        longest_path = max_path(longest_path, new_path);
-    }
-    if doors[1]
-        && y < 3
-        && let Some(new_path) = dfs(passcode, x, y + 1, &format!("{}D", path))
-    {
-        longest_path = max_path(longest_path, new_path);
-    }
-    if doors[2]
-        && x > 0
-        && let Some(new_path) = dfs(passcode, x - 1, y, &format!("{}L", path))
-    {
-        longest_path = max_path(longest_path, new_path);
-    }
-    if doors[3]
-        && x < 3
-        && let Some(new_path) = dfs(passcode, x + 1, y, &format!("{}R", path))
-    {
-        longest_path = max_path(longest_path, new_path);
-    }
-
-    longest_path
+/// The room one step through the door in `direction`, if it stays within the 4x4 grid.
+fn through(room: Point, direction: Direction) -> Option<Point> {
+    room.step(direction)
+        .filter(|next| next.x <= 3 && next.y <= 3)
 }
 
-fn max_path(a: Option<String>, b: String) -> Option<String> {
-    match a {
-        None => Some(b),
-        Some(existing) => {
-            if b.len() > existing.len() {
-                Some(b)
-            } else {
-                Some(existing)
-            }
-        }
-    }
+/// The rooms reachable from `room` through the doors currently open after `path`, with the path
+/// that leads there.
+fn moves(
+    passcode: &str,
+    (room, path): &(Point, String),
+) -> impl Iterator<Item = (Point, String)> + use<> {
+    let (room, path) = (*room, path.clone());
+    Direction::ALL
+        .into_iter()
+        .zip(get_doors(passcode, &path))
+        .filter(|&(_, open)| open)
+        .filter_map(move |(direction, _)| {
+            through(room, direction).map(|next| (next, format!("{path}{}", char::from(direction))))
+        })
 }
 
 pub fn part_one(input: &str) -> Option<String> {
-    bfs(&input[0..input.len() - 1])
+    let passcode = &input[0..input.len() - 1];
+    let path = bfs(
+        (START, String::new()),
+        |state| moves(passcode, state),
+        |(room, _)| *room == VAULT,
+    )?;
+    Some(path.goal().1.clone())
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let longest_path = dfs(&input[0..input.len() - 1], 0, 0, "");
-    longest_path.map(|longest_path| longest_path.len())
+    let passcode = &input[0..input.len() - 1];
+    let path = longest_path(
+        (START, String::new()),
+        |state| moves(passcode, state),
+        |(room, _)| *room == VAULT,
+    )?;
+    Some(path.cost)
 }
 
 #[cfg(test)]
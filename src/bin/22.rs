@@ -1,7 +1,6 @@
 advent_of_code::solution!(22);
 
-use advent_of_code::Point;
-use hashbrown::HashMap;
+use advent_of_code::{Grid, Point};
 use nom::{
     IResult,
     bytes::complete::{tag, take_until},
@@ -12,7 +11,7 @@ use nom::{
 };
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct NodeData {
     pub x: u32,
     pub y: u32,
@@ -91,46 +90,34 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub struct NodeGrid {
-    pub data: HashMap<Point, NodeData>,
-    pub max_x: u32,
-    pub max_y: u32,
+    pub data: Grid<NodeData>,
 }
 impl NodeGrid {
     pub fn parse(data: &[NodeData]) -> Self {
-        let max_x = data.iter().map(|d| d.x).max().unwrap();
-        let max_y = data.iter().map(|d| d.y).max().unwrap();
+        let width = data.iter().map(|d| d.x).max().unwrap() as usize + 1;
+        let height = data.iter().map(|d| d.y).max().unwrap() as usize + 1;
 
-        let mut grid: HashMap<Point, NodeData> = HashMap::new();
+        let mut grid = Grid::new(width, height, NodeData::default());
         data.iter().for_each(|&n| {
-            *grid.entry(Point::from((n.x, n.y))).or_insert(n) = n;
+            grid[Point::from((n.x, n.y))] = n;
         });
 
-        Self {
-            data: grid,
-            max_x,
-            max_y,
-        }
+        Self { data: grid }
     }
 
     pub fn print(&self) {
-        let grid = &self.data;
-
-        (0..self.max_y + 1)
-            .map(|y| {
-                (0..self.max_x + 1)
-                    .map(|x| {
-                        let node = grid.get(&Point::from((x, y))).unwrap();
-                        if node.is_full() {
-                            '#'
-                        } else if node.is_empty() {
-                            ' '
-                        } else {
-                            '.'
-                        }
-                    })
-                    .collect::<String>()
+        print!(
+            "{}",
+            self.data.render(|node| {
+                if node.is_full() {
+                    '#'
+                } else if node.is_empty() {
+                    ' '
+                } else {
+                    '.'
+                }
             })
-            .for_each(|l| println!("{l}"));
+        );
     }
 }
 
@@ -1,20 +1,24 @@
-use advent_of_code::assembunny::{Instruction, State, parse_line};
+use advent_of_code::assembunny::{Machine, State, parse_program};
 
 advent_of_code::solution!(23);
 
 pub fn part_one(input: &str) -> Option<i32> {
-    let instructions: Vec<Instruction> = input.lines().map(parse_line).collect();
-    let mut state = State::new(instructions);
-    state.a = 7;
+    let instructions = parse_program(input).map_err(|e| eprintln!("{e}")).ok()?;
+    let mut state = State::builder(instructions).reg('a', 7).build();
     state.run();
 
-    Some(state.a)
+    Some(state.reg('a'))
 }
 
-pub fn part_two(_input: &str) -> Option<u64> {
-    let factorial: u64 = (1..=12).product();
+pub fn part_two(input: &str) -> Option<i64> {
+    let instructions = parse_program(input).map_err(|e| eprintln!("{e}")).ok()?;
+    let mut state = State::<i64>::builder(instructions)
+        .reg('a', 12)
+        .optimize()
+        .build();
+    state.run();
 
-    Some(factorial + 71 * 72)
+    Some(state.reg('a'))
 }
 
 #[cfg(test)]
@@ -30,6 +34,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(479006712));
+        assert_eq!(result, Some(3));
     }
 }
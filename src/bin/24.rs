@@ -1,6 +1,5 @@
-use advent_of_code::Point;
-use hashbrown::HashMap;
-use pathfinding::prelude::bfs;
+use advent_of_code::Grid;
+use advent_of_code::tsp::Distances;
 
 advent_of_code::solution!(24);
 
@@ -10,119 +9,40 @@ pub enum Type {
     Wall,
 }
 
-pub struct Maze {
-    pub grid: HashMap<Point, Type>,
-    pub start: Point,
-    pub width: usize,
-    pub height: usize,
-    pub node_count: usize,
-}
-
-impl Maze {
-    pub fn parse_input(input: &str) -> Self {
-        let width = input
-            .lines()
-            .next()
-            .unwrap()
-            .chars()
-            .collect::<Vec<_>>()
-            .len();
-        let mut start: Option<Point> = None;
-        let height = input.lines().collect::<Vec<_>>().len();
-        let mut node_count = 0;
-        let grid: HashMap<Point, Type> = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(|(x, ch)| {
-                        let grid_type = match ch {
-                            '#' => Type::Wall,
-                            '.' => Type::Open(None),
-                            d => {
-                                node_count += 1;
-                                let digit = d.to_digit(10).unwrap();
-                                if digit == 0 {
-                                    start = Some(Point::from((x, y)));
-                                }
-                                Type::Open(Some(digit))
-                            }
-                        };
-                        (Point::from((x, y)), grid_type)
-                    })
-                    .collect::<Vec<(Point, Type)>>()
-            })
-            .collect();
+impl TryFrom<char> for Type {
+    type Error = char;
 
-        Self {
-            grid,
-            start: start.unwrap(),
-            height,
-            width,
-            node_count,
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch {
+            '#' => Ok(Type::Wall),
+            '.' => Ok(Type::Open(None)),
+            d => d.to_digit(10).map(|digit| Type::Open(Some(digit))).ok_or(d),
         }
     }
+}
 
-    pub fn successors(&self, point: Point, visited: u32) -> Vec<(Point, u32)> {
-        let neighbors = point.udlr([0, self.height as u32, 0, self.width as u32]);
-        neighbors
-            .iter()
-            .filter_map(|n| match self.grid.get(n) {
-                Some(Type::Open(None)) => Some((*n, visited)),
-                Some(Type::Open(Some(d))) => {
-                    let bit = 1 << d;
-                    if visited & bit == 0 {
-                        Some((*n, visited | bit))
-                    } else {
-                        Some((*n, visited))
-                    }
-                }
-                Some(Type::Wall) | None => None,
-            })
-            .collect::<Vec<(Point, u32)>>()
-    }
-
-    pub fn part1_end_condition(&self, visited: &u32) -> bool {
-        self.node_count as u32 == visited.count_ones()
-    }
-    pub fn part2_end_condition(&self, point: &Point, visited: &u32) -> bool {
-        *point == self.start && self.node_count as u32 == visited.count_ones()
-    }
+/// The distances between the numbered locations of the maze.
+fn parse_input(input: &str) -> Distances<u32> {
+    let grid: Grid<Type> = input.parse().unwrap();
+    let locations = grid.iter().filter_map(|(point, &t)| match t {
+        Type::Open(Some(digit)) => Some((digit, point)),
+        _ => None,
+    });
+    Distances::from_grid(&grid, locations, |&t| t != Type::Wall)
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let maze = Maze::parse_input(input);
-    let start = maze.start;
-
-    let start: (Point, u32) = (start, 1 << 0);
-
-    let result = bfs(
-        &start,
-        |&(point, visited)| maze.successors(point, visited),
-        |(_point, visited)| maze.part1_end_condition(visited),
-    );
-
-    let result = result.expect("No answer found");
-
-    Some(result.len() - 1)
+    let tour = parse_input(input)
+        .shortest_path(&0)
+        .expect("No answer found");
+    Some(tour.cost)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let maze = Maze::parse_input(input);
-    let start = maze.start;
-
-    let start: (Point, u32) = (start, 1 << 0);
-
-    let result = bfs(
-        &start,
-        |&(point, visited)| maze.successors(point, visited),
-        |(point, visited)| maze.part2_end_condition(point, visited),
-    );
-
-    let result = result.expect("No answer found");
-
-    Some(result.len() - 1)
+    let tour = parse_input(input)
+        .shortest_cycle(&0)
+        .expect("No answer found");
+    Some(tour.cost)
 }
 
 #[cfg(test)]
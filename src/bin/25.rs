@@ -0,0 +1,49 @@
+use advent_of_code::assembunny::{Machine, State, parse_program};
+use hashbrown::HashSet;
+
+advent_of_code::solution!(25, 1);
+
+/// Number of instructions a candidate may execute between two transmitted values.
+const STEPS_PER_OUTPUT: u64 = 1_000_000;
+
+/// Checks that the program transmits `0, 1, 0, 1, ...` forever when started with `a`.
+///
+/// The signal is accepted once the machine is back in a state it was in at an earlier `out`,
+/// because from there on it will repeat the (already verified) values. Candidates that halt or
+/// spin without transmitting are rejected.
+fn produces_clock_signal(machine: &State, a: i32) -> bool {
+    let mut state = machine.clone();
+    state.set_reg('a', a);
+
+    let mut seen = HashSet::new();
+    let mut expected = 0;
+    while let Ok(value) = state.next_output(STEPS_PER_OUTPUT) {
+        if value != expected {
+            return false;
+        }
+        if !seen.insert(state.snapshot()) {
+            return true;
+        }
+        expected = 1 - expected;
+    }
+
+    false
+}
+
+pub fn part_one(input: &str) -> Option<i32> {
+    let instructions = parse_program(input).map_err(|e| eprintln!("{e}")).ok()?;
+    let machine = State::new(instructions);
+
+    (1..).find(|&a| produces_clock_signal(&machine, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(30));
+    }
+}
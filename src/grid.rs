@@ -0,0 +1,273 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::{Bounds, Point};
+
+/// A dense, rectangular 2D grid, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid by calling `f` for every point, row by row.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::from((x, y))))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (point.x as usize) < self.width && (point.y as usize) < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// The cells of row `y`, from left to right.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The cells of column `x`, from top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "column {x} is outside a grid {} wide",
+            self.width
+        );
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::from((x, y))))
+    }
+
+    /// Every point of the grid with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Finds the first point, row by row, whose cell matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The rectangle covered by the grid.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.width as u32, self.height as u32)
+    }
+
+    /// The points above, below, left and right of `point` that lie within the grid.
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + use<T> {
+        point.neighbors4(self.bounds())
+    }
+
+    /// Shifts row `y` right by `by` cells, wrapping around.
+    pub fn rotate_row(&mut self, y: usize, by: usize) {
+        let width = self.width;
+        self.row_mut(y).rotate_right(by % width.max(1));
+    }
+
+    /// Shifts column `x` down by `by` cells, wrapping around.
+    pub fn rotate_column(&mut self, x: usize, by: usize) {
+        assert!(
+            x < self.width,
+            "column {x} is outside a grid {} wide",
+            self.width
+        );
+        for _ in 0..by % self.height.max(1) {
+            for y in (1..self.height).rev() {
+                self.cells
+                    .swap(y * self.width + x, (y - 1) * self.width + x);
+            }
+        }
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid with one character per cell and a newline after every row.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).chain(['\n']).collect::<String>())
+            .collect()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridParseError {
+    /// A row is not as wide as the first one.
+    Ragged { y: usize, width: usize },
+    /// A character does not convert to a cell.
+    Cell { x: usize, y: usize, ch: char },
+}
+
+impl Display for GridParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridParseError::Ragged { y, width } => {
+                write!(
+                    f,
+                    "row {y} is {width} characters wide, unlike the first row"
+                )
+            }
+            GridParseError::Cell { x, y, ch } => write!(f, "unexpected `{ch}` at ({x}, {y})"),
+        }
+    }
+}
+
+impl std::error::Error for GridParseError {}
+
+/// Parses a character map, one row per line. Empty lines are ignored.
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = GridParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().filter(|line| !line.is_empty()).collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let row_width = line.chars().count();
+            if row_width != width {
+                return Err(GridParseError::Ragged {
+                    y,
+                    width: row_width,
+                });
+            }
+            for (x, ch) in line.chars().enumerate() {
+                cells.push(T::try_from(ch).map_err(|_| GridParseError::Cell { x, y, ch })?);
+            }
+        }
+
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_indexes() {
+        let grid: Grid<char> = "ab\ncd\nef\n".parse().unwrap();
+        assert_eq!((grid.width, grid.height), (2, 3));
+        assert_eq!(grid[Point::from((1u32, 2))], 'f');
+        assert_eq!(grid.get(Point::from((2u32, 0))), None);
+        assert_eq!(grid.row(1), ['c', 'd']);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.position(|&c| c == 'd'), Some(Point::from((1u32, 1))));
+
+        assert_eq!(
+            "ab\nc".parse::<Grid<char>>(),
+            Err(GridParseError::Ragged { y: 1, width: 1 })
+        );
+    }
+
+    #[test]
+    fn neighbors_respect_bounds() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(
+            grid.neighbors(Point::from((0u32, 0))).collect::<Vec<_>>(),
+            vec![Point::from((0u32, 1)), Point::from((1u32, 0))]
+        );
+        assert_eq!(grid.neighbors(Point::from((1u32, 1))).count(), 3);
+    }
+
+    #[test]
+    fn rotates_rows_and_columns() {
+        let mut grid = Grid::from_fn(3, 2, |p| p.y * 3 + p.x);
+        grid.rotate_row(0, 1);
+        assert_eq!(grid.row(0), [2, 0, 1]);
+        grid.rotate_column(2, 3);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![5, 1]);
+        assert_eq!(
+            grid.render(|&v| char::from_digit(v, 10).unwrap()),
+            "205\n341\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside a grid 3 wide")]
+    fn rejects_columns_outside_the_grid() {
+        let grid = Grid::from_fn(3, 2, |p| p.y * 3 + p.x);
+        let _ = grid.column(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside a grid 3 wide")]
+    fn rejects_rotating_columns_outside_the_grid() {
+        let mut grid = Grid::from_fn(3, 2, |p| p.y * 3 + p.x);
+        grid.rotate_column(3, 1);
+    }
+}
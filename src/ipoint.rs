@@ -0,0 +1,185 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::{Direction, Point};
+
+/// An integer type usable as an [`IPoint`] coordinate.
+pub trait Coordinate:
+    Copy
+    + Default
+    + Ord
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + TryFrom<u32>
+    + TryInto<u32>
+{
+    const ONE: Self;
+
+    /// `|a - b|`, without overflowing for unsigned types.
+    fn distance(a: Self, b: Self) -> Self {
+        if a > b { a - b } else { b - a }
+    }
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(impl Coordinate for $t {
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_coordinate!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+/// A point with coordinates of any integer type, so that unlike [`Point`] it can walk into
+/// negative coordinates when `T` is signed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IPoint<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coordinate> IPoint<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves one step in `direction`, with up being towards negative `y` like [`Point::up`].
+    pub fn step(self, direction: impl Into<Direction>) -> Self {
+        match direction.into() {
+            Direction::Up => Self::new(self.x, self.y - T::ONE),
+            Direction::Down => Self::new(self.x, self.y + T::ONE),
+            Direction::Left => Self::new(self.x - T::ONE, self.y),
+            Direction::Right => Self::new(self.x + T::ONE, self.y),
+        }
+    }
+
+    /// The taxicab distance: the number of orthogonal steps between the points.
+    pub fn manhattan(&self, other: &Self) -> T {
+        T::distance(self.x, other.x) + T::distance(self.y, other.y)
+    }
+
+    /// The chessboard distance: the number of steps between the points when diagonal steps are
+    /// allowed.
+    pub fn chebyshev(&self, other: &Self) -> T {
+        T::distance(self.x, other.x).max(T::distance(self.y, other.y))
+    }
+}
+
+impl<T> From<(T, T)> for IPoint<T> {
+    fn from(value: (T, T)) -> Self {
+        Self {
+            x: value.0,
+            y: value.1,
+        }
+    }
+}
+
+impl<T: Coordinate> TryFrom<Point> for IPoint<T> {
+    type Error = <T as TryFrom<u32>>::Error;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x: T::try_from(value.x)?,
+            y: T::try_from(value.y)?,
+        })
+    }
+}
+
+/// Fails for negative coordinates, or ones that don't fit in a `u32`.
+impl<T: Coordinate> TryFrom<IPoint<T>> for Point {
+    type Error = <T as TryInto<u32>>::Error;
+
+    fn try_from(value: IPoint<T>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x: value.x.try_into()?,
+            y: value.y.try_into()?,
+        })
+    }
+}
+
+impl<T: Coordinate> Add for IPoint<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coordinate> AddAssign for IPoint<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coordinate> Sub for IPoint<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coordinate> SubAssign for IPoint<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales both coordinates.
+impl<T: Coordinate> Mul<T> for IPoint<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Coordinate + Neg<Output = T>> Neg for IPoint<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_distances() {
+        let a = IPoint::new(3, -4);
+        let b = IPoint::new(-1, 2);
+
+        assert_eq!(a + b, IPoint::new(2, -2));
+        assert_eq!(a - b, IPoint::new(4, -6));
+        assert_eq!(b * 3, IPoint::new(-3, 6));
+        assert_eq!(-a, IPoint::new(-3, 4));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!(
+            a.step(Direction::Up).step(Direction::Left),
+            IPoint::new(2, -5)
+        );
+
+        let (c, d) = (IPoint::<u8>::new(1, 9), IPoint::<u8>::new(4, 2));
+        assert_eq!(c.manhattan(&d), 10);
+        assert_eq!(d.chebyshev(&c), 7);
+    }
+
+    #[test]
+    fn converts_to_and_from_point() {
+        let point = Point::from((3u32, 7));
+        assert_eq!(IPoint::<i64>::try_from(point), Ok(IPoint::new(3, 7)));
+        assert_eq!(Point::try_from(IPoint::new(3, 7)), Ok(point));
+        assert!(Point::try_from(IPoint::new(-1, 7)).is_err());
+        assert!(IPoint::<i8>::try_from(Point::from((300u32, 0))).is_err());
+    }
+}
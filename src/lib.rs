@@ -1,5 +1,13 @@
 pub mod assembunny;
+pub mod grid;
+pub mod ipoint;
+pub mod ocr;
+pub mod search;
 pub mod template;
+pub mod tsp;
+
+pub use grid::Grid;
+pub use ipoint::IPoint;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Point {
@@ -98,13 +106,124 @@ impl Point {
             None => None,
         }
     }
-    // let up_left = y > 0 && x > 0 && grid.contains(&(x - 1, y - 1));
-    // let up_right = y > 0 && grid.contains(&(x + 1, y - 1));
-    // let down_left = x > 0 && grid.contains(&(x - 1, y + 1));
-    // let down_right = grid.contains(&(x + 1, y + 1));
+    /// Moves one step in `direction`, or returns `None` when that would leave the `u32` range.
+    pub fn step(&self, direction: impl Into<Direction>) -> Option<Self> {
+        match direction.into() {
+            Direction::Up => self.up(None),
+            Direction::Down => self.down(None),
+            Direction::Left => self.left(None),
+            Direction::Right => self.right(None),
+        }
+    }
+    /// Moves by `(dx, dy)`, or returns `None` when that would leave the `u32` range.
+    pub fn offset(&self, dx: i32, dy: i32) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+    /// The points above, below, left and right of this one that lie within `bounds`, in that
+    /// order.
+    pub fn neighbors4(self, bounds: Bounds) -> impl Iterator<Item = Self> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(direction))
+            .filter(move |&p| bounds.contains(p))
+    }
+    /// The eight points surrounding this one that lie within `bounds`, in reading order.
+    pub fn neighbors8(self, bounds: Bounds) -> impl Iterator<Item = Self> {
+        const OFFSETS: [(i32, i32); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        OFFSETS
+            .into_iter()
+            .filter_map(move |(dx, dy)| self.offset(dx, dy))
+            .filter(move |&p| bounds.contains(p))
+    }
+    /// The points at chessboard distance `n` that lie within `bounds`: the border of the square
+    /// centred on this point, clockwise from its top left corner. `ring(1, ..)` yields the same
+    /// points as [`Point::neighbors8`].
+    pub fn ring(self, n: u32, bounds: Bounds) -> impl Iterator<Item = Self> {
+        let n = n as i32;
+        let side = 2 * n;
+        (0..(4 * side).max(1))
+            .filter_map(move |i| {
+                if n == 0 {
+                    return Some(self);
+                }
+                let k = i % side;
+                let (dx, dy) = match i / side {
+                    0 => (-n + k, -n),
+                    1 => (n, -n + k),
+                    2 => (n - k, n),
+                    _ => (-n, n - k),
+                };
+                self.offset(dx, dy)
+            })
+            .filter(move |&p| bounds.contains(p))
+    }
+    /// The points at taxicab distance `n` that lie within `bounds`: the border of the diamond
+    /// centred on this point, clockwise from its top. `diamond(1, ..)` yields the same points as
+    /// [`Point::neighbors4`].
+    pub fn diamond(self, n: u32, bounds: Bounds) -> impl Iterator<Item = Self> {
+        let n = n as i32;
+        (0..(4 * n).max(1))
+            .filter_map(move |i| {
+                if n == 0 {
+                    return Some(self);
+                }
+                let k = i % n;
+                let (dx, dy) = match i / n {
+                    0 => (k, -n + k),
+                    1 => (n - k, k),
+                    2 => (-k, n - k),
+                    _ => (-n + k, -k),
+                };
+                self.offset(dx, dy)
+            })
+            .filter(move |&p| bounds.contains(p))
+    }
 }
 
-// TODO: Add "one_up", etc. methods
+/// A rectangle of points, with `min` inclusive and `max` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    /// Every point representable by [`Point`], apart from those with a coordinate of `u32::MAX`.
+    pub const ALL: Self = Self {
+        min: Point { x: 0, y: 0 },
+        max: Point {
+            x: u32::MAX,
+            y: u32::MAX,
+        },
+    };
+
+    /// The rectangle from the origin up to, but not including, `width` and `height`.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            min: Point { x: 0, y: 0 },
+            max: Point {
+                x: width,
+                y: height,
+            },
+        }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..self.max.x).contains(&point.x) && (self.min.y..self.max.y).contains(&point.y)
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CardinalDirection {
@@ -114,7 +233,64 @@ pub enum CardinalDirection {
     East,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl CardinalDirection {
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::West => Self::South,
+            Self::South => Self::East,
+            Self::East => Self::North,
+        }
+    }
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+    pub fn opposite(self) -> Self {
+        self.turn_left().turn_left()
+    }
+}
+
+/// Parses `N`, `E`, `S` or `W`.
+impl TryFrom<char> for CardinalDirection {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'N' => Ok(Self::North),
+            'E' => Ok(Self::East),
+            'S' => Ok(Self::South),
+            'W' => Ok(Self::West),
+            c => Err(c),
+        }
+    }
+}
+
+/// North is up, as in [`Point::up`].
+impl From<Direction> for CardinalDirection {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Self::North,
+            Direction::Down => Self::South,
+            Direction::Left => Self::West,
+            Direction::Right => Self::East,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     Up,
     Down,
@@ -122,8 +298,85 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    /// In the order of [`Point::udlr`].
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    pub fn turn_left(self) -> Self {
+        CardinalDirection::from(self).turn_left().into()
+    }
+    pub fn turn_right(self) -> Self {
+        CardinalDirection::from(self).turn_right().into()
+    }
+    pub fn turn(self, turn: Turn) -> Self {
+        CardinalDirection::from(self).turn(turn).into()
+    }
+    pub fn opposite(self) -> Self {
+        CardinalDirection::from(self).opposite().into()
+    }
+}
+
+/// Parses `U`, `D`, `L` or `R`.
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' => Ok(Self::Up),
+            'D' => Ok(Self::Down),
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            c => Err(c),
+        }
+    }
+}
+
+impl From<Direction> for char {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+impl From<CardinalDirection> for Direction {
+    fn from(value: CardinalDirection) -> Self {
+        match value {
+            CardinalDirection::North => Self::Up,
+            CardinalDirection::South => Self::Down,
+            CardinalDirection::West => Self::Left,
+            CardinalDirection::East => Self::Right,
+        }
+    }
+}
+
+/// A change of heading, as in `R2, L3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// Parses `L` or `R`.
+impl TryFrom<char> for Turn {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            c => Err(c),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
     #[test]
@@ -140,4 +393,57 @@ mod tests {
         assert_eq!(point.left_n(10, None), Some((0u32, 10).into()));
         assert_eq!(point.left_n(1, None), Some((9u32, 10).into()));
     }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn(Turn::Left), Direction::Down);
+        assert_eq!(CardinalDirection::East.opposite(), CardinalDirection::West);
+        assert_eq!(
+            CardinalDirection::try_from('N').map(|d| d.turn(Turn::try_from('L').unwrap())),
+            Ok(CardinalDirection::West)
+        );
+        assert_eq!(Direction::try_from('x'), Err('x'));
+        assert_eq!(
+            Direction::ALL.map(char::from).iter().collect::<String>(),
+            "UDLR"
+        );
+
+        let point = Point::from((0u32, 5));
+        assert_eq!(point.step(CardinalDirection::North), Some((0u32, 4).into()));
+        assert_eq!(point.step(Direction::Left), None);
+    }
+
+    #[test]
+    fn neighbors() {
+        let corner = Point::from((0u32, 0));
+        let bounds = Bounds::new(3, 3);
+        assert_eq!(
+            corner.neighbors4(bounds).collect::<Vec<_>>(),
+            vec![(0u32, 1).into(), (1u32, 0).into()]
+        );
+        assert_eq!(corner.neighbors8(bounds).count(), 3);
+
+        let centre = Point::from((1u32, 1));
+        assert_eq!(centre.neighbors4(bounds).count(), 4);
+        assert_eq!(
+            centre.ring(1, bounds).collect::<HashSet<_>>(),
+            centre.neighbors8(bounds).collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            centre.diamond(1, bounds).collect::<HashSet<_>>(),
+            centre.neighbors4(bounds).collect::<HashSet<_>>()
+        );
+        assert_eq!(centre.ring(0, bounds).collect::<Vec<_>>(), vec![centre]);
+
+        let point = Point::from((5u32, 5));
+        assert_eq!(point.ring(3, Bounds::ALL).count(), 24);
+        assert!(
+            point
+                .diamond(3, Bounds::ALL)
+                .all(|p| p.x.abs_diff(5) + p.y.abs_diff(5) == 3)
+        );
+        assert_eq!(point.diamond(3, Bounds::ALL).count(), 12);
+        assert_eq!(point.diamond(3, Bounds::new(6, 6)).count(), 4);
+    }
 }
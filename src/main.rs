@@ -1,4 +1,6 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, debug_bunny, download, read, scaffold, solve, time, verify,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -27,14 +29,23 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            json: bool,
         },
         All {
             release: bool,
+            json: bool,
+        },
+        Verify {
+            release: bool,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            json: bool,
+        },
+        DebugBunny {
+            file: String,
         },
         #[cfg(feature = "today")]
         Today,
@@ -46,17 +57,26 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                json: args.contains("--json"),
+            },
+            Some("verify") => AppArguments::Verify {
+                release: args.contains("--release"),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let json = args.contains("--json");
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    json,
                 }
             }
+            Some("debug-bunny") => AppArguments::DebugBunny {
+                file: args.free_from_str()?,
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
@@ -73,6 +93,7 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                json: args.contains("--json"),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -102,8 +123,15 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::All { release, json } => all::handle(release, json),
+            AppArguments::Verify { release } => verify::handle(release),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                json,
+            } => time::handle(day, all, store, json),
+            AppArguments::DebugBunny { file } => debug_bunny::handle(&file),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day, download, overwrite } => {
@@ -117,7 +145,8 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                json,
+            } => solve::handle(day, release, dhat, submit, json),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
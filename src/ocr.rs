@@ -0,0 +1,138 @@
+//! Reads the block letters that some puzzles draw on a screen, like day 8's, so that the answer
+//! can be returned as a `String` instead of being read off the terminal.
+//!
+//! Letters are 6 pixels high and sit in cells 5 pixels wide: 4 columns for the letter, then an
+//! empty one, except for the wider `Y`.
+use std::fmt::Display;
+
+use crate::{Grid, Point};
+
+pub const LETTER_WIDTH: usize = 5;
+pub const LETTER_HEIGHT: usize = 6;
+
+/// The letters that appear in puzzle answers, one row per string.
+const FONT: [(char, [&str; LETTER_HEIGHT]); 18] = [
+    ('A', [".##..", "#..#.", "#..#.", "####.", "#..#.", "#..#."]),
+    ('B', ["###..", "#..#.", "###..", "#..#.", "#..#.", "###.."]),
+    ('C', [".##..", "#..#.", "#....", "#....", "#..#.", ".##.."]),
+    ('E', ["####.", "#....", "###..", "#....", "#....", "####."]),
+    ('F', ["####.", "#....", "###..", "#....", "#....", "#...."]),
+    ('G', [".##..", "#..#.", "#....", "#.##.", "#..#.", ".###."]),
+    ('H', ["#..#.", "#..#.", "####.", "#..#.", "#..#.", "#..#."]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..##.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#..#.", "#.#..", "##...", "#.#..", "#.#..", "#..#."]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "####."]),
+    ('O', [".##..", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+    ('P', ["###..", "#..#.", "#..#.", "###..", "#....", "#...."]),
+    ('R', ["###..", "#..#.", "#..#.", "###..", "#.#..", "#..#."]),
+    ('S', [".###.", "#....", "#....", ".##..", "...#.", "###.."]),
+    ('U', ["#..#.", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####.", "...#.", "..#..", ".#...", "#....", "####."]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// The screen is not [`LETTER_HEIGHT`] pixels high.
+    Height(usize),
+    /// The letter in cell `index`, counting from the left, is not in the font.
+    UnknownLetter { index: usize },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::Height(height) => write!(
+                f,
+                "letters are {LETTER_HEIGHT} pixels high, but the screen is {height}"
+            ),
+            OcrError::UnknownLetter { index } => write!(f, "letter {index} is not in the font"),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Reads the letters on `screen`, where `true` is a lit pixel. Empty cells, such as unused space
+/// at the end of the screen, are skipped.
+pub fn recognise(screen: &Grid<bool>) -> Result<String, OcrError> {
+    if screen.height != LETTER_HEIGHT {
+        return Err(OcrError::Height(screen.height));
+    }
+
+    let cells = screen.width.div_ceil(LETTER_WIDTH);
+    let mut text = String::with_capacity(cells);
+    for index in 0..cells {
+        let lit = |x: usize, y: usize| {
+            let point = Point::from((index * LETTER_WIDTH + x, y));
+            screen.get(point).copied().unwrap_or(false)
+        };
+        let is_letter = |rows: &[&str; LETTER_HEIGHT]| {
+            rows.iter().enumerate().all(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .all(|(x, pixel)| lit(x, y) == (pixel == '#'))
+            })
+        };
+
+        if (0..LETTER_HEIGHT).all(|y| (0..LETTER_WIDTH).all(|x| !lit(x, y))) {
+            continue;
+        }
+        let (letter, _) = FONT
+            .iter()
+            .find(|(_, rows)| is_letter(rows))
+            .ok_or(OcrError::UnknownLetter { index })?;
+        text.push(*letter);
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(rows: [&str; LETTER_HEIGHT]) -> Grid<bool> {
+        let width = rows[0].len();
+        Grid::from_fn(width, LETTER_HEIGHT, |p| {
+            rows[p.y as usize].as_bytes()[p.x as usize] == b'#'
+        })
+    }
+
+    #[test]
+    fn reads_letters() {
+        let hello = screen([
+            "#..#.####.#....#.....##..........",
+            "#..#.#....#....#....#..#.........",
+            "####.###..#....#....#..#.........",
+            "#..#.#....#....#....#..#.........",
+            "#..#.#....#....#....#..#.........",
+            "#..#.####.####.####..##..........",
+        ]);
+        assert_eq!(recognise(&hello), Ok("HELLO".to_string()));
+
+        let font = Grid::from_fn(FONT.len() * LETTER_WIDTH, LETTER_HEIGHT, |p| {
+            let (_, rows) = FONT[p.x as usize / LETTER_WIDTH];
+            rows[p.y as usize].as_bytes()[p.x as usize % LETTER_WIDTH] == b'#'
+        });
+        assert_eq!(recognise(&font), Ok("ABCEFGHIJKLOPRSUYZ".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_letters() {
+        let blob = screen([
+            ".##..####.",
+            "#..#.####.",
+            "#..#.####.",
+            "####.####.",
+            "#..#.####.",
+            "#..#.####.",
+        ]);
+        assert_eq!(recognise(&blob), Err(OcrError::UnknownLetter { index: 1 }));
+        assert_eq!(
+            recognise(&Grid::new(10, 3, false)),
+            Err(OcrError::Height(3))
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Graph searches over arbitrary state types. A graph is described by a `successors` function
+//! from a state to the states it leads to, with their cost where edges are weighted.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::{DefaultHashBuilder, HashMap, HashTable, hash_table};
+
+/// The result of a search: the states from start to goal, both included, and what it cost to
+/// walk between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C = usize> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    /// The number of steps taken, one less than the number of states.
+    pub fn len(&self) -> usize {
+        self.nodes.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The state the path ends in.
+    pub fn goal(&self) -> &N {
+        self.nodes
+            .last()
+            .expect("a path contains at least its start")
+    }
+}
+
+/// Every state seen so far, with the index of the state it was reached from.
+struct Visited<N> {
+    nodes: Vec<(N, Option<usize>)>,
+}
+
+impl<N: Clone> Visited<N> {
+    fn push(&mut self, node: N, parent: Option<usize>) -> usize {
+        self.nodes.push((node, parent));
+        self.nodes.len() - 1
+    }
+
+    fn path_to<C>(&self, mut index: usize, cost: C) -> Path<N, C> {
+        let mut nodes = vec![self.nodes[index].0.clone()];
+        while let Some(parent) = self.nodes[index].1 {
+            nodes.push(self.nodes[parent].0.clone());
+            index = parent;
+        }
+        nodes.reverse();
+        Path { cost, nodes }
+    }
+}
+
+/// Finds a path with the fewest steps from `start` to a state for which `success` holds.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let hasher = DefaultHashBuilder::default();
+    let mut visited = Visited { nodes: vec![] };
+    // the index in `visited` of every state seen so far, found by hashing the state there, so
+    // that each state is only stored once.
+    let mut seen: HashTable<usize> = HashTable::new();
+    let mut queue = VecDeque::new();
+
+    seen.insert_unique(hasher.hash_one(&start), 0, |_| unreachable!());
+    queue.push_back((visited.push(start, None), 0));
+
+    while let Some((index, steps)) = queue.pop_front() {
+        let node = &visited.nodes[index].0;
+        if success(node) {
+            return Some(visited.path_to(index, steps));
+        }
+        for next in successors(node) {
+            let nodes = &visited.nodes;
+            let entry = seen.entry(
+                hasher.hash_one(&next),
+                |&i| nodes[i].0 == next,
+                |&i| hasher.hash_one(&nodes[i].0),
+            );
+            if let hash_table::Entry::Vacant(entry) = entry {
+                entry.insert(nodes.len());
+                queue.push_back((visited.push(next, Some(index)), steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds a cheapest path from `start` to a state for which `success` holds. Costs must not be
+/// negative.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    success: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), success)
+}
+
+/// Finds a cheapest path from `start` to a state for which `success` holds, exploring the states
+/// that `heuristic` estimates to be closest to a goal first. The heuristic must never
+/// overestimate the remaining cost, or the path found may not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut visited = Visited { nodes: vec![] };
+    // the cheapest known cost to reach each state
+    let mut best: HashMap<N, C> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    let zero = C::default();
+    best.insert(start.clone(), zero);
+    heap.push(Reverse((
+        heuristic(&start),
+        zero,
+        visited.push(start, None),
+    )));
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = visited.nodes[index].0.clone();
+        if best[&node] < cost {
+            // a cheaper way here was found after this entry was queued
+            continue;
+        }
+        if success(&node) {
+            return Some(visited.path_to(index, cost));
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if best.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            let estimate = next_cost + heuristic(&next);
+            heap.push(Reverse((
+                estimate,
+                next_cost,
+                visited.push(next, Some(index)),
+            )));
+        }
+    }
+
+    None
+}
+
+/// Every state reachable from `start` in at most `max_steps` steps, with the fewest steps
+/// needed to reach it.
+pub fn reachable_within<N, I>(
+    start: N,
+    max_steps: usize,
+    mut successors: impl FnMut(&N) -> I,
+) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut steps = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    steps.insert(start.clone(), 0);
+    queue.push_back((start, 0));
+
+    while let Some((node, distance)) = queue.pop_front() {
+        if distance == max_steps {
+            continue;
+        }
+        for next in successors(&node) {
+            if let Entry::Vacant(entry) = steps.entry(next.clone()) {
+                entry.insert(distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    steps
+}
+
+/// Finds a path with the most steps from `start` to a state for which `success` holds. Goal
+/// states are not walked through.
+///
+/// Every path is explored, so the states reachable from `start` must not form a cycle, as is the
+/// case when a state records the path taken to reach it.
+pub fn longest_path<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut longest: Option<Vec<N>> = None;
+    let mut path = vec![];
+    // the successors of every state on `path` that are still to be explored
+    let mut stack = vec![vec![start]];
+
+    while let Some(pending) = stack.last_mut() {
+        let Some(node) = pending.pop() else {
+            stack.pop();
+            path.pop();
+            continue;
+        };
+        if success(&node) {
+            if longest.as_ref().is_none_or(|l| l.len() < path.len() + 1) {
+                let mut found = path.clone();
+                found.push(node);
+                longest = Some(found);
+            }
+            continue;
+        }
+        let mut next: Vec<N> = successors(&node).into_iter().collect();
+        next.reverse();
+        path.push(node);
+        stack.push(next);
+    }
+
+    longest.map(|nodes| Path {
+        cost: nodes.len() - 1,
+        nodes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted graph where the direct edge from 0 to 3 is the most expensive way there.
+    fn weighted(node: &u32) -> Vec<(u32, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 4), (3, 10)],
+            1 => vec![(2, 1), (3, 7)],
+            2 => vec![(3, 2)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn finds_shortest_paths() {
+        let path = bfs(1u32, |&n| [n + 1, n * 2], |&n| n == 10).unwrap();
+        assert_eq!(path.nodes, vec![1, 2, 4, 5, 10]);
+        assert_eq!((path.cost, path.len()), (4, 4));
+        assert_eq!(
+            bfs(1u32, |&n| [n * 2], |&n| n == 3 || n > 100),
+            Some(Path {
+                cost: 7,
+                nodes: vec![1, 2, 4, 8, 16, 32, 64, 128]
+            })
+        );
+        assert_eq!(bfs(0u32, |_| [], |&n| n == 1), None);
+
+        let path = dijkstra(0, weighted, |&n| n == 3).unwrap();
+        assert_eq!((path.cost, path.nodes), (4, vec![0, 1, 2, 3]));
+
+        let path = astar(0, weighted, |&n| 3 - n, |&n| n == 3).unwrap();
+        assert_eq!((path.cost, path.nodes), (4, vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn floods_and_finds_longest_paths() {
+        let reachable = reachable_within(0i32, 2, |&n| [n - 1, n + 1]);
+        assert_eq!(reachable.len(), 5);
+        assert_eq!(reachable[&-2], 2);
+
+        // binary strings without two consecutive ones, ending at length 4
+        let successors = |s: &String| {
+            let mut next = vec![format!("{s}0")];
+            if !s.ends_with('1') {
+                next.push(format!("{s}1"));
+            }
+            next
+        };
+        let path = longest_path(String::new(), successors, |s| s.len() == 4).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.goal(), "0000");
+        assert_eq!(
+            longest_path(String::new(), |_| vec![], |s| !s.is_empty()),
+            None
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::{collections::HashMap, fmt::Display, fs, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+/// Represents the accepted answers for a single day, as stored in `data/answers/<day>.json`:
+///
+/// ```json
+/// { "part_1": 42, "part_2": "ABCDEFGH" }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// The outcome of checking one part against its accepted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: Option<String>,
+    },
+    /// No answer has been recorded for the part.
+    Missing,
+    /// The solution did not report the part, e.g. because it panicked before getting to it.
+    NotReported,
+}
+
+impl Answers {
+    #[must_use]
+    pub fn path(day: Day) -> String {
+        format!("./data/answers/{day}.json")
+    }
+
+    /// Rehydrate the answers for a day from its JSON file. If not present, returns no answers.
+    pub fn read_from_file(day: Day) -> Result<Self, String> {
+        match fs::read_to_string(Self::path(day)) {
+            Ok(s) => Answers::try_from(s),
+            Err(_) => Ok(Answers::default()),
+        }
+    }
+
+    /// Compares the `actual` result of a part (1 or 2) against its accepted answer.
+    #[must_use]
+    pub fn verify(&self, part: u8, actual: Option<&str>) -> Verdict {
+        let expected = match part {
+            1 => &self.part_1,
+            _ => &self.part_2,
+        };
+        match expected {
+            None => Verdict::Missing,
+            Some(expected) if actual == Some(expected.as_str()) => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+                actual: actual.map(str::to_string),
+            },
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail {
+                expected,
+                actual: Some(actual),
+            } => write!(f, "FAIL (expected {expected}, got {actual})"),
+            Verdict::Fail {
+                expected,
+                actual: None,
+            } => write!(f, "FAIL (expected {expected}, got no answer)"),
+            Verdict::Missing => write!(f, "MISSING"),
+            Verdict::NotReported => write!(f, "FAIL (the solution did not report this part)"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Answers may be stored as strings or, for convenience, as integers.
+fn answer_from_json(value: &JsonValue) -> Option<Option<String>> {
+    match value {
+        JsonValue::Null => Some(None),
+        JsonValue::String(s) => Some(Some(s.clone())),
+        JsonValue::Number(n) if n.fract() == 0.0 => Some(Some(format!("{n:.0}"))),
+        _ => None,
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let part = |key: &str| {
+            json.get(key)
+                .map_or(Some(None), answer_from_json)
+                .ok_or(format!("Expected answers.{key} to be null, a string or an integer."))
+        };
+
+        Ok(Answers {
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answers, Verdict};
+
+    #[test]
+    fn deserializes_answers() {
+        let answers =
+            Answers::try_from(r#"{ "part_1": 10152, "part_2": "EFEYKFRFIJ" }"#.to_string())
+                .unwrap();
+        assert_eq!(answers.part_1.as_deref(), Some("10152"));
+        assert_eq!(answers.part_2.as_deref(), Some("EFEYKFRFIJ"));
+
+        let answers = Answers::try_from(r#"{ "part_1": "1", "part_2": null }"#.to_string());
+        assert_eq!(answers.unwrap().part_2, None);
+
+        assert!(Answers::try_from(r#"{ "part_1": 1.5 }"#.to_string()).is_err());
+        assert!(Answers::try_from("[]".to_string()).is_err());
+    }
+
+    #[test]
+    fn verifies_parts() {
+        let answers = Answers {
+            part_1: Some("42".into()),
+            part_2: None,
+        };
+        assert_eq!(answers.verify(1, Some("42")), Verdict::Pass);
+        assert_eq!(
+            answers.verify(1, None),
+            Verdict::Fail {
+                expected: "42".into(),
+                actual: None
+            }
+        );
+        assert_eq!(answers.verify(2, Some("7")), Verdict::Missing);
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::{fs, process};
+
+use crate::assembunny::disasm::disassemble;
+use crate::assembunny::{
+    Instruction, Machine, Snapshot, State, Step, parse_program, register_index, register_name,
+};
+
+const HELP: &str = "\
+commands:
+  s, step [n]           execute the next n instructions (default 1)
+  c, continue           run until a breakpoint, a watched register changes or the program halts,
+                        for at most 10000000 instructions
+  b, break <pc>         set a breakpoint
+  d, delete <pc>        remove a breakpoint
+  w, watch <reg>        stop when a register changes
+  u, unwatch <reg>      stop watching a register
+  r, regs               print registers and the next instruction
+  l, disasm             print the program
+  set <reg|pc> <value>  change a register or the program counter
+  save <file>           write registers, program counter and program to a JSON file
+  load <file>           restore a state written by `save`
+  q, quit               exit the debugger";
+
+/// How many instructions `continue` executes before giving control back, so that a program
+/// that never halts doesn't hang the debugger.
+const CONTINUE_LIMIT: u64 = 10_000_000;
+
+struct Debugger {
+    state: State<i64>,
+    original: Vec<Instruction>,
+    breakpoints: BTreeSet<i64>,
+    watches: BTreeSet<usize>,
+}
+
+impl Debugger {
+    fn print_regs(&self) {
+        let regs = self
+            .state
+            .regs
+            .iter()
+            .enumerate()
+            .map(|(r, value)| format!("{}={value}", register_name(r)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.state.instructions.get(self.state.pos as usize) {
+            Some(instruction) => println!("{regs}  pc={} {instruction}", self.state.pos),
+            None => println!("{regs}  pc={} (halted)", self.state.pos),
+        }
+    }
+
+    fn disasm(&self) {
+        let source = disassemble(&self.state.instructions, Some(&self.original));
+        for (pos, line) in source.lines().enumerate() {
+            let pos = pos as i64;
+            let marker = if pos == self.state.pos { "=>" } else { "  " };
+            let breakpoint = if self.breakpoints.contains(&pos) {
+                "*"
+            } else {
+                " "
+            };
+            println!("{marker}{breakpoint}{pos:>4}  {line}");
+        }
+    }
+
+    /// Executes one instruction, returning `false` if the debugger should stop.
+    fn step(&mut self) -> bool {
+        let before = self.state.regs.clone();
+        match self.state.step() {
+            Step::Halted => {
+                println!("program halted");
+                return false;
+            }
+            Step::Output(value) => println!("out: {value}"),
+            Step::Continue => {}
+        }
+
+        let mut keep_going = true;
+        for &reg in &self.watches {
+            let (Some(old), Some(new)) = (before.get(reg), self.state.regs.get(reg)) else {
+                continue;
+            };
+            if old != new {
+                println!("watch: {} changed {old} -> {new}", register_name(reg));
+                keep_going = false;
+            }
+        }
+        keep_going
+    }
+
+    fn continue_running(&mut self) {
+        for _ in 0..CONTINUE_LIMIT {
+            if !self.step() {
+                return;
+            }
+            if self.breakpoints.contains(&self.state.pos) {
+                println!("breakpoint at {}", self.state.pos);
+                return;
+            }
+        }
+        println!("stopped after {CONTINUE_LIMIT} instructions");
+    }
+
+    /// Looks up a register of the loaded program by name.
+    fn register(&self, name: &str) -> Option<usize> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => register_index(c).filter(|&r| r < self.state.regs.len()),
+            _ => None,
+        }
+    }
+
+    /// Handles one line of input, returning `false` when the user asked to quit.
+    fn handle(&mut self, line: &str) -> bool {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => return true,
+            ["q" | "quit"] => return false,
+            ["h" | "help"] => println!("{HELP}"),
+            ["s" | "step"] => {
+                self.step();
+            }
+            ["s" | "step", n] => match n.parse::<u64>() {
+                Ok(n) => {
+                    for _ in 0..n {
+                        if !self.step() {
+                            break;
+                        }
+                    }
+                }
+                Err(_) => println!("invalid step count: {n}"),
+            },
+            ["c" | "continue"] => self.continue_running(),
+            ["b" | "break", pc] => match pc.parse() {
+                Ok(pc) => {
+                    self.breakpoints.insert(pc);
+                }
+                Err(_) => println!("invalid position: {pc}"),
+            },
+            ["d" | "delete", pc] => match pc.parse() {
+                Ok(pc) => {
+                    self.breakpoints.remove(&pc);
+                }
+                Err(_) => println!("invalid position: {pc}"),
+            },
+            ["w" | "watch", reg] => match self.register(reg) {
+                Some(reg) => {
+                    self.watches.insert(reg);
+                }
+                None => println!("invalid register: {reg}"),
+            },
+            ["u" | "unwatch", reg] => match self.register(reg) {
+                Some(reg) => {
+                    self.watches.remove(&reg);
+                }
+                None => println!("invalid register: {reg}"),
+            },
+            ["r" | "regs"] => {}
+            ["l" | "disasm"] => {
+                self.disasm();
+                return true;
+            }
+            ["set", target, value] => match (self.register(target), value.parse()) {
+                (_, Err(_)) => println!("invalid value: {value}"),
+                (Some(reg), Ok(value)) => self.state.regs[reg] = value,
+                (None, Ok(value)) if *target == "pc" => self.state.pos = value,
+                (None, _) => println!("invalid register: {target}"),
+            },
+            ["save", file] => {
+                match self.state.snapshot().store_file(file) {
+                    Ok(()) => println!("saved to {file}"),
+                    Err(e) => println!("failed to write \"{file}\": {e}"),
+                }
+                return true;
+            }
+            ["load", file] => match Snapshot::read_file(file) {
+                Ok(snapshot) => {
+                    self.state.restore(&snapshot);
+                    // the loaded program may use fewer registers.
+                    let registers = self.state.regs.len();
+                    for &reg in self.watches.iter().filter(|&&reg| reg >= registers) {
+                        println!(
+                            "unwatched {}: not in the loaded program",
+                            register_name(reg)
+                        );
+                    }
+                    self.watches.retain(|&reg| reg < registers);
+                }
+                Err(e) => println!("{file}: {e}"),
+            },
+            _ => {
+                println!("unknown command: {line}");
+                return true;
+            }
+        }
+        self.print_regs();
+        true
+    }
+}
+
+/// Loads an assembunny program, or a state written by `save` if `file` ends in `.json`.
+fn load(file: &str) -> Result<Snapshot<i64>, String> {
+    if file.ends_with(".json") {
+        return Snapshot::read_file(file);
+    }
+
+    let source = fs::read_to_string(file).map_err(|e| format!("Failed to read \"{file}\": {e}"))?;
+    let instructions = parse_program(&source).map_err(|e| format!("{file}: {e}"))?;
+    Ok(State::new(instructions).snapshot())
+}
+
+pub fn handle(file: &str) {
+    let snapshot = match load(file) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let mut state = State::builder(snapshot.instructions.clone())
+        .registers(snapshot.regs.len())
+        .build();
+    state.restore(&snapshot);
+    let mut debugger = Debugger {
+        original: snapshot.instructions,
+        state,
+        breakpoints: BTreeSet::new(),
+        watches: BTreeSet::new(),
+    };
+
+    println!(
+        "Loaded {} instructions. Type `help` for a list of commands.",
+        debugger.state.instructions.len()
+    );
+    debugger.print_regs();
+
+    let stdin = io::stdin();
+    loop {
+        print!("(bunny) ");
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                if !debugger.handle(line.trim()) {
+                    break;
+                }
+            }
+        }
+    }
+}
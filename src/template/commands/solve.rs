@@ -1,8 +1,10 @@
+use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 
 use crate::template::Day;
+use crate::template::runner::PartReport;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>, json: bool) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -18,17 +20,40 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
 
     cmd_args.push("--".to_string());
 
+    if json {
+        cmd_args.push("--json".to_string());
+    }
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
     }
 
+    // with `--json`, stdout only holds the reports, so anything else the solution prints, such
+    // as debug output, goes to stderr.
+    let stdout = if json {
+        Stdio::piped()
+    } else {
+        Stdio::inherit()
+    };
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .spawn()
         .unwrap();
 
+    if let Some(stdout) = cmd.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let line = line.unwrap();
+            if PartReport::from_json_line(&line).is_ok() {
+                println!("{line}");
+            } else {
+                eprintln!("{line}");
+            }
+        }
+    }
+
     cmd.wait().unwrap();
 }
@@ -0,0 +1,60 @@
+use std::ops::RangeInclusive;
+use std::process;
+
+use crate::template::answers::{Answers, Verdict};
+use crate::template::run_multi::run_multi;
+use crate::template::{ANSI_BOLD, ANSI_RESET, Day, all_days};
+
+/// The parts of a day's puzzle. Day 25 only has one: its second star is awarded for finishing
+/// every other puzzle.
+fn parts(day: Day) -> RangeInclusive<u8> {
+    if day == crate::day!(25) { 1..=1 } else { 1..=2 }
+}
+
+/// Runs every solution and checks its answers against `data/answers`. Exits with an error if
+/// any part returns a different answer than the accepted one, or if a solution fails before
+/// reporting all of its parts.
+pub fn handle(is_release: bool) {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    let mut crashed = false;
+
+    run_multi(
+        &all_days().collect(),
+        is_release,
+        false,
+        false,
+        |day, run| {
+            crashed |= !run.status.success();
+
+            let answers = match Answers::read_from_file(day) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("Could not read {}: {e}", Answers::path(day));
+                    failed += 1;
+                    return;
+                }
+            };
+
+            for part in parts(day) {
+                let verdict = match run.reports.iter().find(|report| report.part == part) {
+                    Some(report) => answers.verify(part, report.answer.as_deref()),
+                    None => Verdict::NotReported,
+                };
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    Verdict::Fail { .. } | Verdict::NotReported => failed += 1,
+                    Verdict::Missing => missing += 1,
+                }
+                println!("Answer {part}: {verdict}");
+            }
+        },
+    );
+
+    println!(
+        "\n{ANSI_BOLD}Verified:{ANSI_RESET} {passed} passed, {failed} failed, {missing} missing"
+    );
+
+    if failed > 0 || crashed {
+        process::exit(1);
+    }
+}
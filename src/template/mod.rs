@@ -5,8 +5,11 @@ pub mod commands;
 pub mod runner;
 
 pub use day::*;
+pub use params::Params;
 
+mod answers;
 mod day;
+mod params;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -36,9 +39,35 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
+/// Reads the [`Params`] for an input file, e.g. `data/examples/08.params` for `data/examples/08.txt`.
+/// Without such a file, the defaults, which are meant for the real input, are used.
+#[must_use]
+pub fn read_params<P: Params>(folder: &str, day: Day) -> P {
+    parse_params_file(folder, &format!("{day}.params"))
+}
+
+/// Reads the [`Params`] for an input file with a part suffix, e.g. `data/examples/01-2.params`.
+#[must_use]
+pub fn read_params_part<P: Params>(folder: &str, day: Day, part: u8) -> P {
+    parse_params_file(folder, &format!("{day}-{part}.params"))
+}
+
+fn parse_params_file<P: Params>(folder: &str, file: &str) -> P {
+    let cwd = env::current_dir().unwrap();
+    let filepath = cwd.join("data").join(folder).join(file);
+    match fs::read_to_string(&filepath) {
+        Ok(s) => P::parse(&s)
+            .unwrap_or_else(|e| panic!("could not parse {}: {e}", filepath.display())),
+        Err(_) => P::default(),
+    }
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// With a trailing `params: Type`, the parts take the day's [`Params`] as a second argument, read
+/// with [`read_params`] for the real input.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,14 +79,18 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, params: $params:ty) => {
+        $crate::solution!(@params $day, $params, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, params: $params:ty) => {
+        $crate::solution!(@params $day, $params, [part_one, 1]);
+    };
+    ($day:expr, 2, params: $params:ty) => {
+        $crate::solution!(@params $day, $params, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
-        /// The current day.
-        const DAY: $crate::template::Day = $crate::day!($day);
-
-        #[cfg(feature = "dhat-heap")]
-        #[global_allocator]
-        static ALLOC: dhat::Alloc = dhat::Alloc;
+        $crate::solution!(@day $day);
 
         fn main() {
             use $crate::template::runner::*;
@@ -65,4 +98,22 @@ macro_rules! solution {
             $( run_part($func, &input, DAY, $part); )*
         }
     };
+    (@params $day:expr, $params:ty, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@day $day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            let params: $params = $crate::template::read_params("inputs", DAY);
+            $( run_part(|input| $func(input, &params), &input, DAY, $part); )*
+        }
+    };
+    (@day $day:expr) => {
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!($day);
+
+        #[cfg(feature = "dhat-heap")]
+        #[global_allocator]
+        static ALLOC: dhat::Alloc = dhat::Alloc;
+    };
 }
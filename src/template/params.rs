@@ -0,0 +1,108 @@
+/// Puzzle constants that differ between the real input and the examples, such as a screen size or
+/// a target value. Declare them with [`params!`](crate::params), which sets the real values as
+/// defaults; examples override them in a sidecar file, e.g. `data/examples/08.params`:
+///
+/// ```text
+/// # the example screen is smaller
+/// cols = 7
+/// rows = 3
+/// ```
+pub trait Params: Default {
+    /// Sets the field named `key` from its textual `value`.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// Parses `key = value` lines over the defaults. Empty lines and lines starting with `#`
+    /// are ignored.
+    fn parse(s: &str) -> Result<Self, String> {
+        let mut params = Self::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected `key = value`, found `{line}`"))?;
+            params.set(key.trim(), value.trim())?;
+        }
+        Ok(params)
+    }
+}
+
+/// Declares a [`Params`] struct. Every field is given with its value for the real input:
+///
+/// ```ignore
+/// advent_of_code::params! {
+///     pub struct Params {
+///         cols: usize = 50,
+///         rows: usize = 6,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $( $(#[$field_meta:meta])* $field:ident: $ty:ty = $default:expr ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $( $(#[$field_meta])* pub $field: $ty, )*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $( $field: $default, )*
+                }
+            }
+        }
+
+        impl $crate::template::Params for $name {
+            fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key {
+                    $(
+                        stringify!($field) => {
+                            self.$field = value
+                                .parse()
+                                .map_err(|_| format!("invalid value `{value}` for `{key}`"))?;
+                        }
+                    )*
+                    _ => return Err(format!("unknown parameter `{key}`")),
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::params! {
+        struct Screen {
+            cols: usize = 50,
+            rows: usize = 6,
+            label: String = "real".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_over_defaults() {
+        assert_eq!(
+            Screen::parse("# the example\ncols = 7\n\nlabel=example\n"),
+            Ok(Screen {
+                cols: 7,
+                rows: 6,
+                label: "example".to_string(),
+            })
+        );
+        assert_eq!(Screen::parse(""), Ok(Screen::default()));
+        assert!(Screen::parse("cols = many").is_err());
+        assert!(Screen::parse("depth = 3").is_err());
+        assert!(Screen::parse("cols 7").is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, io, process::ExitStatus};
 
+use crate::template::runner::{PartReport, format_duration, print_result};
 use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
@@ -7,7 +8,18 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Runs the solutions for `days_to_run`, passing the outcome of every day that has been
+/// scaffolded to `on_day`.
+///
+/// With `is_json`, every part is printed as a JSON line (see [`PartReport::to_json_line`])
+/// instead of the human readable output.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    is_json: bool,
+    mut on_day: impl FnMut(Day, &SolutionRun),
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -16,36 +28,72 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
     all_days()
         .filter(|day| days_to_run.contains(day))
         .for_each(|day| {
-            if need_space {
-                println!();
+            if !is_json {
+                if need_space {
+                    println!();
+                }
+                need_space = true;
+
+                println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+                println!("------");
             }
-            need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            let Some(run) = child_commands::run_solution(day, is_timed, is_release).unwrap() else {
+                if !is_json {
+                    println!("Not solved.");
+                }
+                return;
+            };
+
+            for report in &run.reports {
+                if is_json {
+                    println!("{}", report.to_json_line());
+                } else {
+                    print_result(
+                        &report.answer,
+                        &format!("Part {}", report.part),
+                        &format_duration(&report.duration, report.samples),
+                    );
+                }
+            }
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            if !is_json {
+                if !run.status.success() {
+                    println!("Failed with {}.", run.status);
+                } else if run.reports.is_empty() {
+                    println!("Not solved.");
+                }
+            }
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
+            if !run.reports.is_empty() {
+                let val = child_commands::parse_exec_time(&run.reports, day);
                 timings.push(val);
             }
+            on_day(day, &run);
         });
 
     if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
-        println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
-        );
+        if !is_json {
+            println!(
+                "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
         Some(timings)
     } else {
         None
     }
 }
 
+/// What a solution binary reported, and how it exited.
+#[derive(Debug)]
+pub struct SolutionRun {
+    /// The reports of the parts that finished.
+    pub reports: Vec<PartReport>,
+    pub status: ExitStatus,
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -65,10 +113,11 @@ pub fn get_path_for_bin(day: Day) -> String {
 }
 
 /// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// This module encapsulates interaction with these binaries, both invoking them as well as collecting the part reports they print.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
+    use super::{get_path_for_bin, Error, SolutionRun};
     use crate::template::Day;
+    use crate::template::runner::PartReport;
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -76,11 +125,16 @@ pub mod child_commands {
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, collecting the report of every part that finished.
+    /// Returns `None` for days that have not been scaffolded.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+    ) -> Result<Option<SolutionRun>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok(None);
         }
 
         let day_padded = day.to_string();
@@ -90,14 +144,17 @@ pub mod child_commands {
             args.push("--release");
         }
 
+        // children report their parts as JSON lines.
+        args.push("--");
+        args.push("--json");
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward output to stderr while grabbing the reports from stdout.
 
         let mut cmd = Command::new("cargo")
             .args(&args)
@@ -108,27 +165,37 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
         let thread = thread::spawn(move || {
             stderr.lines().for_each(|line| {
                 eprintln!("{}", line.unwrap());
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
+        let reports = parse_reports(stdout.lines().map(Result::unwrap));
 
         thread.join().unwrap();
-        cmd.wait()?;
+        let status = cmd.wait()?;
 
-        Ok(output)
+        Ok(Some(SolutionRun { reports, status }))
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
+    /// Collects the part reports from a solution's output. Anything else a solution prints, such
+    /// as debug output, is forwarded to stderr.
+    pub fn parse_reports(lines: impl IntoIterator<Item = String>) -> Vec<PartReport> {
+        lines
+            .into_iter()
+            .filter_map(|line| match PartReport::from_json_line(&line) {
+                Ok(report) => Some(report),
+                Err(_) => {
+                    eprintln!("{line}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Collects the timings of benched parts.
+    pub fn parse_exec_time(reports: &[PartReport], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
             part_1: None,
@@ -136,59 +203,26 @@ pub mod child_commands {
             total_nanos: 0_f64,
         };
 
-        output
+        // unsolved parts, and parts that were run once rather than benched, don't count.
+        for report in reports
             .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
-
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+            .filter(|report| report.answer.is_some() && report.samples > 1)
+        {
+            let timing_str = format!("{:.1?}", report.duration);
+            match report.part {
+                1 => timings.part_1 = Some(timing_str),
+                2 => timings.part_2 = Some(timing_str),
+                _ => continue,
+            }
 
-                timings.total_nanos += nanos;
-            });
+            #[allow(clippy::cast_precision_loss)]
+            let nanos = report.duration.as_nanos() as f64;
+            timings.total_nanos += nanos;
+        }
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
-
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
-    }
-
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
     #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
@@ -205,53 +239,72 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use std::time::Duration;
+
+        use super::{parse_exec_time, parse_reports};
+        use crate::template::runner::PartReport;
 
         use crate::day;
 
-        #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+        fn report(part: u8, answer: Option<&str>, nanos: u64, samples: u128) -> PartReport {
+            PartReport {
+                day: day!(1),
+                part,
+                answer: answer.map(str::to_string),
+                duration: Duration::from_nanos(nanos),
+                samples,
+            }
         }
 
         #[test]
-        fn parses_with_patterns_in_input() {
+        fn parses_execution_times() {
             let res = parse_exec_time(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
+                    report(1, Some("0"), 74, 100000),
+                    report(2, Some("10"), 74_130_000, 99999),
                 ],
                 day!(1),
             );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_approx_eq!(res.total_nanos, 74130074_f64);
+            assert_eq!(res.part_1.unwrap(), "74.0ns");
+            assert_eq!(res.part_2.unwrap(), "74.1ms");
         }
 
         #[test]
-        fn parses_missing_parts() {
+        fn ignores_unbenched_and_missing_parts() {
             let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
+                &[report(1, Some("1"), 2_000_000_000, 1), report(2, None, 0, 10)],
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn writes_reports_in_key_order() {
+            assert_eq!(
+                report(1, Some("42"), 74130, 10000).to_json_line(),
+                r#"{"day":"01","part":1,"answer":"42","status":"solved","nanos":74130,"samples":10000}"#
+            );
+            assert_eq!(
+                report(2, None, 5, 1).to_json_line(),
+                r#"{"day":"01","part":2,"answer":null,"status":"unsolved","nanos":5,"samples":1}"#
+            );
+        }
+
+        #[test]
+        fn parses_reports_with_patterns_in_answers() {
+            let reports = vec![
+                report(1, Some("@ @ @ ( ) ms (2s @ 5 samples)"), 2_000_000_000, 5),
+                report(2, Some("#..#\n####"), 100_000_000, 1),
+                report(2, None, 5, 10),
+            ];
+            let lines = reports
+                .iter()
+                .map(PartReport::to_json_line)
+                .chain(["debug output".to_string()]);
+            assert_eq!(parse_reports(lines), reports);
+        }
     }
 }
@@ -1,27 +1,162 @@
 /// Encapsulates code that interacts with solution functions.
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{Write, stdout};
 use std::process::Output;
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use tinyjson::JsonValue;
+
 use crate::template::ANSI_BOLD;
 use crate::template::{ANSI_ITALIC, ANSI_RESET, Day, aoc_cli};
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = if is_json() {
+        run_timed(func, input, |_| {})
+    } else {
+        run_timed(func, input, |result| print_result(result, &part_str, ""))
+    };
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    if is_json() {
+        let report = PartReport {
+            day,
+            part,
+            answer: result.as_ref().map(ToString::to_string),
+            duration,
+            samples,
+        };
+        println!("{}", report.to_json_line());
+    } else {
+        print_result(&result, &part_str, &format_duration(&duration, samples));
+    }
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// With `--json`, every part prints a [`PartReport`] on a single line instead of the human
+/// readable output.
+fn is_json() -> bool {
+    env::args().any(|x| x == "--json")
+}
+
+/// The outcome of running one part of a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartReport {
+    pub day: Day,
+    pub part: u8,
+    /// The answer, or `None` if the part is not solved.
+    pub answer: Option<String>,
+    /// The duration of a single run, averaged over `samples` runs.
+    pub duration: Duration,
+    pub samples: u128,
+}
+
+/// The keys of a serialized [`PartReport`], in the order they are written.
+const REPORT_KEYS: [&str; 6] = ["day", "part", "answer", "status", "nanos", "samples"];
+
+impl PartReport {
+    /// Serializes the report as a single line of JSON:
+    ///
+    /// ```json
+    /// {"day":"01","part":1,"answer":"42","status":"solved","nanos":74130,"samples":10000}
+    /// ```
+    ///
+    /// `status` is `"solved"` or `"unsolved"`, in which case `answer` is `null`.
+    pub fn to_json_line(&self) -> String {
+        let json = JsonValue::from(self);
+        let map: &HashMap<String, JsonValue> = json.get().expect("a part report is an object");
+
+        // tinyjson keeps objects in a `HashMap`, so the keys are written one by one to keep
+        // their order stable.
+        let fields: Vec<String> = REPORT_KEYS
+            .iter()
+            .map(|key| {
+                let value = map[*key].stringify().expect("a part report is valid JSON");
+                format!("\"{key}\":{value}")
+            })
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+
+    /// Parses a line printed by [`PartReport::to_json_line`].
+    pub fn from_json_line(line: &str) -> Result<Self, String> {
+        let json = JsonValue::from_str(line).or(Err("not valid JSON."))?;
+        PartReport::try_from(&json)
+    }
+}
+
+impl From<&PartReport> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &PartReport) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(value.part.into()));
+        map.insert(
+            "answer".into(),
+            match &value.answer {
+                Some(answer) => JsonValue::String(answer.clone()),
+                None => JsonValue::Null,
+            },
+        );
+        map.insert(
+            "status".into(),
+            JsonValue::String(if value.answer.is_some() { "solved" } else { "unsolved" }.into()),
+        );
+        map.insert(
+            "nanos".into(),
+            JsonValue::Number(value.duration.as_nanos() as f64),
+        );
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartReport {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part report to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .filter(|n| n.fract() == 0.0 && *n >= 0.0)
+                .ok_or(format!("Expected report.{key} to be a positive integer."))
+        };
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected report.day to be a Day struct.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected report.answer to be null or string.")?;
+
+        Ok(PartReport {
+            day,
+            part: number("part")? as u8,
+            answer: answer.cloned(),
+            duration: Duration::from_nanos(number("nanos")? as u64),
+            samples: number("samples")? as u128,
+        })
+    }
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -55,8 +190,10 @@ fn run_timed<I: Clone, T>(
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
     let mut stdout = stdout();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if !is_json() {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout.flush();
+    }
 
     let bench_iterations =
         (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
@@ -86,7 +223,7 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+pub(crate) fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
@@ -94,7 +231,7 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+pub(crate) fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -0,0 +1,187 @@
+//! Shortest routes visiting every one of a set of labelled points, as in day 24. Distances
+//! between the points are measured with one BFS per point, and the best visiting order is then
+//! found with the Held-Karp dynamic programme, which takes `O(2^n n^2)` time for `n` points.
+use crate::search::reachable_within;
+use crate::{Grid, Point};
+
+/// A route through every point, starting with the one it was asked to start from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<L> {
+    pub cost: usize,
+    /// The labels in visiting order. For a closed tour, the start is repeated at the end.
+    pub order: Vec<L>,
+}
+
+/// The distances between every pair of labelled points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances<L> {
+    labels: Vec<L>,
+    /// `matrix[i][j]` is the distance from `labels[i]` to `labels[j]`, or `None` if there is no
+    /// way there.
+    matrix: Vec<Vec<Option<usize>>>,
+}
+
+impl<L: Clone + PartialEq> Distances<L> {
+    /// Creates the distance table from a matrix, indexed like `labels`.
+    pub fn new(labels: Vec<L>, matrix: Vec<Vec<Option<usize>>>) -> Self {
+        assert!(
+            matrix.len() == labels.len() && matrix.iter().all(|row| row.len() == labels.len()),
+            "the matrix must be square, with one row per label"
+        );
+        Self { labels, matrix }
+    }
+
+    /// Measures the distances between `points` on `grid`, moving up, down, left and right
+    /// between cells for which `passable` holds.
+    pub fn from_grid<T>(
+        grid: &Grid<T>,
+        points: impl IntoIterator<Item = (L, Point)>,
+        passable: impl Fn(&T) -> bool,
+    ) -> Self {
+        let (labels, points): (Vec<L>, Vec<Point>) = points.into_iter().unzip();
+        let matrix = points
+            .iter()
+            .map(|&from| {
+                let steps = reachable_within(from, usize::MAX, |&p| {
+                    grid.neighbors(p).filter(|&n| passable(&grid[n]))
+                });
+                points.iter().map(|to| steps.get(to).copied()).collect()
+            })
+            .collect();
+        Self { labels, matrix }
+    }
+
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    fn index_of(&self, label: &L) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
+
+    /// The distance from `from` to `to`, or `None` if either is unknown or there is no way there.
+    pub fn get(&self, from: &L, to: &L) -> Option<usize> {
+        self.matrix[self.index_of(from)?][self.index_of(to)?]
+    }
+
+    /// The shortest route from `start` that visits every point, ending wherever is cheapest.
+    pub fn shortest_path(&self, start: &L) -> Option<Tour<L>> {
+        self.solve(self.index_of(start)?, false)
+    }
+
+    /// The shortest route from `start` that visits every point and returns to `start`.
+    pub fn shortest_cycle(&self, start: &L) -> Option<Tour<L>> {
+        self.solve(self.index_of(start)?, true)
+    }
+
+    fn solve(&self, start: usize, closed: bool) -> Option<Tour<L>> {
+        let n = self.labels.len();
+        let full = (1usize << n) - 1;
+        let distance = |i: usize, j: usize| self.matrix[i][j];
+
+        // cost[mask][j]: the cheapest way to start at `start`, visit exactly the points in
+        // `mask` and end at `j`, with the point visited before `j` in `previous[mask][j]`.
+        let mut cost = vec![vec![None; n]; 1 << n];
+        let mut previous = vec![vec![0; n]; 1 << n];
+        cost[1 << start][start] = Some(0);
+
+        for mask in 1..=full {
+            if mask & (1 << start) == 0 {
+                continue;
+            }
+            for last in (0..n).filter(|&last| mask & (1 << last) != 0) {
+                let Some(so_far) = cost[mask][last] else {
+                    continue;
+                };
+                for next in (0..n).filter(|&next| mask & (1 << next) == 0) {
+                    let Some(step) = distance(last, next) else {
+                        continue;
+                    };
+                    let candidate = so_far + step;
+                    let entry = &mut cost[mask | 1 << next][next];
+                    if entry.is_none_or(|best| candidate < best) {
+                        *entry = Some(candidate);
+                        previous[mask | 1 << next][next] = last;
+                    }
+                }
+            }
+        }
+
+        let (total, mut last) = (0..n)
+            .filter_map(|last| {
+                let so_far = cost[full][last]?;
+                let back = if closed { distance(last, start)? } else { 0 };
+                Some((so_far + back, last))
+            })
+            .min()?;
+
+        let mut order = vec![];
+        let mut mask = full;
+        while mask != 1 << start {
+            order.push(self.labels[last].clone());
+            let before = previous[mask][last];
+            mask &= !(1 << last);
+            last = before;
+        }
+        order.push(self.labels[start].clone());
+        order.reverse();
+        if closed {
+            order.push(self.labels[start].clone());
+        }
+
+        Some(Tour { cost: total, order })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_open_and_closed_tours() {
+        // four towns along a road, with `a` between `b` and `c`
+        let position = [5usize, 0, 7, 20];
+        let matrix = position
+            .iter()
+            .map(|a| position.iter().map(|b| Some(a.abs_diff(*b))).collect())
+            .collect();
+        let distances = Distances::new(vec!['a', 'b', 'c', 'd'], matrix);
+
+        assert_eq!(
+            distances.shortest_path(&'a'),
+            Some(Tour {
+                cost: 25,
+                order: vec!['a', 'b', 'c', 'd']
+            })
+        );
+        let cycle = distances.shortest_cycle(&'a').unwrap();
+        assert_eq!(cycle.cost, 40);
+        assert_eq!(
+            (cycle.order.first(), cycle.order.last()),
+            (Some(&'a'), Some(&'a'))
+        );
+        assert_eq!(distances.shortest_path(&'e'), None);
+    }
+
+    #[test]
+    fn measures_grid_distances() {
+        let grid: Grid<char> = "#####\n#0.1#\n#.#.#\n#2..#\n#####\n".parse().unwrap();
+        let points = grid
+            .iter()
+            .filter_map(|(p, c)| c.to_digit(10).map(|d| (d, p)));
+        let distances = Distances::from_grid(&grid, points, |&c| c != '#');
+
+        assert_eq!(distances.get(&0, &1), Some(2));
+        assert_eq!(distances.get(&1, &2), Some(4));
+        assert_eq!(distances.shortest_path(&0).map(|tour| tour.cost), Some(6));
+        assert_eq!(distances.shortest_cycle(&0).map(|tour| tour.cost), Some(8));
+
+        let walled: Grid<char> = "0#1\n".parse().unwrap();
+        let points = walled
+            .iter()
+            .filter_map(|(p, c)| c.to_digit(10).map(|d| (d, p)));
+        let distances = Distances::from_grid(&walled, points, |&c| c != '#');
+        assert_eq!(distances.get(&0, &1), None);
+        assert_eq!(distances.shortest_path(&0), None);
+    }
+}